@@ -0,0 +1,107 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Compares the bulk encoding of bytes with encoding them one at a time.
+
+#![feature(test)]
+
+extern crate test;
+
+use canonical::{Canon, CanonError, EncodeToVec, Sink, Source};
+use test::{black_box, Bencher};
+
+const MIB: usize = 1024 * 1024;
+
+/// A byte that does not override the slice methods of `Canon`, and is thus
+/// encoded element by element
+#[derive(Clone, Copy, Default)]
+struct Byte(u8);
+
+impl Canon for Byte {
+    fn encode(&self, sink: &mut Sink) {
+        self.0.encode(sink)
+    }
+
+    fn decode(source: &mut Source) -> Result<Self, CanonError> {
+        u8::decode(source).map(Byte)
+    }
+
+    fn encoded_len(&self) -> usize {
+        1
+    }
+}
+
+fn bytes() -> Vec<u8> {
+    (0..MIB).map(|i| i as u8).collect()
+}
+
+#[bench]
+fn encode_vec_u8(b: &mut Bencher) {
+    let vec = bytes();
+    b.bytes = MIB as u64;
+    b.iter(|| black_box(&vec).encode_to_vec());
+}
+
+#[bench]
+fn encode_vec_per_byte(b: &mut Bencher) {
+    let vec: Vec<Byte> = bytes().into_iter().map(Byte).collect();
+    b.bytes = MIB as u64;
+    b.iter(|| black_box(&vec).encode_to_vec());
+}
+
+#[bench]
+fn decode_vec_u8(b: &mut Bencher) {
+    let encoded = bytes().encode_to_vec();
+    b.bytes = MIB as u64;
+    b.iter(|| {
+        let mut source = Source::new(black_box(&encoded));
+        Vec::<u8>::decode(&mut source).unwrap()
+    });
+}
+
+#[bench]
+fn decode_vec_per_byte(b: &mut Bencher) {
+    let encoded = bytes().encode_to_vec();
+    b.bytes = MIB as u64;
+    b.iter(|| {
+        let mut source = Source::new(black_box(&encoded));
+        Vec::<Byte>::decode(&mut source).unwrap()
+    });
+}
+
+#[bench]
+fn encode_array_u8(b: &mut Bencher) {
+    let array = [0xa5u8; 4096];
+    b.bytes = 4096;
+    b.iter(|| black_box(&array).encode_to_vec());
+}
+
+#[bench]
+fn encode_array_per_byte(b: &mut Bencher) {
+    let array = [Byte(0xa5); 4096];
+    b.bytes = 4096;
+    b.iter(|| black_box(&array).encode_to_vec());
+}
+
+#[bench]
+fn decode_array_u8(b: &mut Bencher) {
+    let encoded = [0xa5u8; 4096];
+    b.bytes = 4096;
+    b.iter(|| {
+        let mut source = Source::new(black_box(&encoded));
+        <[u8; 4096]>::decode(&mut source).unwrap()
+    });
+}
+
+#[bench]
+fn decode_array_per_byte(b: &mut Bencher) {
+    let encoded = [0xa5u8; 4096];
+    b.bytes = 4096;
+    b.iter(|| {
+        let mut source = Source::new(black_box(&encoded));
+        <[Byte; 4096]>::decode(&mut source).unwrap()
+    });
+}
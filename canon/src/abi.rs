@@ -0,0 +1,82 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! The ABI of the `canon` wasm import module
+//!
+//! On `wasm32` the store is bridged to the host through the following
+//! imports from the `canon` module. Pointers and lengths are `i32`, and a hash
+//! is 32 bytes.
+//!
+//! - `put(buf, len, ret_hash) -> version` stores `len` bytes and writes their
+//!   hash, returning the version byte of their `Id`.
+//! - `get(hash, buf, len) -> status` writes the `len` bytes of `hash`.
+//! - `hash(buf, len, ret_hash) -> version` hashes `len` bytes, returning the
+//!   version byte of their `Id`.
+//! - `hash_version() -> version` returns the version byte of the `Id`s the host
+//!   hashes for, as given by its hash configuration.
+//! - `hash_with(version, buf, len, ret_hash) -> status` hashes `len` bytes as
+//!   recorded by an `Id` version, returning `NOT_FOUND` if the host does not
+//!   know its hash configuration.
+//! - `put_many(bufs, lens, count, ret_hashes) -> version` stores `count`
+//!   values, with their lengths in an array of `i32` and their bytes
+//!   concatenated, returning the version byte of their `Id`s.
+//! - `get_many(hashes, lens, count, bufs) -> status` is the reverse of
+//!   `put_many`, stopping at the first failure.
+//!
+//! The `status` returned is one of `OK`, `NOT_FOUND` or `CORRUPTED`, the
+//! latter meaning the stored value does not have the requested length. The
+//! `version` returned along with a hash is that of the hash configuration it
+//! was computed with, so that it stays correct when the host changes its
+//! configuration between calls.
+//!
+//! # Versions
+//!
+//! A module reports the version of the ABI it was built against through an
+//! exported function `canon_abi_version() -> i32`. Modules without the export
+//! use version 0, which only has the `put`, `get` and `hash` imports, all
+//! returning nothing, and in which the host is expected to abort when `get`
+//! fails.
+//! Building with the `legacy-abi` feature produces such modules, for hosts
+//! that only support version 0.
+//!
+//! Modules of version 0 assume the host hashes with BLAKE2b without a
+//! domain, the default hash configuration. Hosts must refuse to run them
+//! under any other configuration.
+
+use crate::canon::CanonError;
+
+/// The latest version of the ABI
+pub const VERSION: i32 = 1;
+
+/// The name of the function exported to report the version of the ABI
+pub const VERSION_EXPORT: &str = "canon_abi_version";
+
+/// The operation succeeded
+pub const OK: i32 = 0;
+/// The value could not be found in the store
+pub const NOT_FOUND: i32 = 1;
+/// The value in the store does not have the requested length
+pub const CORRUPTED: i32 = 2;
+
+/// Converts the result of a store operation into a status code
+pub fn status(result: Result<(), CanonError>) -> i32 {
+    match result {
+        Ok(()) => OK,
+        Err(CanonError::NotFound) => NOT_FOUND,
+        Err(_) => CORRUPTED,
+    }
+}
+
+/// Converts a status code into the result of a store operation
+///
+/// Unknown status codes are treated as corruption.
+pub fn result(status: i32) -> Result<(), CanonError> {
+    match status {
+        OK => Ok(()),
+        NOT_FOUND => Err(CanonError::NotFound),
+        _ => Err(CanonError::Corrupted),
+    }
+}
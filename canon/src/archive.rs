@@ -0,0 +1,282 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Self-contained archives of store entries
+//!
+//! An archive holds the entries reachable from a set of root `Id`s, to move
+//! them between stores. It consists of a header followed by one record per
+//! entry, until the end of the archive:
+//!
+//! ```text
+//! header:  magic "CANONCAR" | version: u8 | root count: u32 | roots
+//! root:    id
+//! record:  id | bytes
+//! id:      id version: u8 | len: u32 | hash: [u8; 32]
+//! ```
+//!
+//! Integers are little endian, and `bytes` are the `len` bytes of the entry
+//! with the given id. Inline roots are written in the header, but have no
+//! record.
+//!
+//! The store can not tell which `Id`s an entry refers to, so `export` takes a
+//! function returning the children of each entry it visits, as `Walk` does.
+//! That function has to know the type of each entry it is given.
+//!
+//! `import` checks and stores every record, while a `Reader` yields them as
+//! they are read.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use crate::canon::CanonError;
+use crate::id::{Id, IdHash, HASH_BYTES};
+use crate::store::{Store, StoreRef};
+use crate::visit::{Order, Walk};
+
+/// The magic bytes starting every archive
+pub const MAGIC: [u8; 8] = *b"CANONCAR";
+
+/// The version of the archive format written by `export`
+pub const VERSION: u8 = 1;
+
+const ID_BYTES: usize = 1 + 4 + HASH_BYTES;
+
+/// The possible errors when exporting or importing an archive
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing the archive failed
+    Io(io::Error),
+    /// An entry is missing from the store, or does not match its `Id`
+    Canon(CanonError),
+    /// The archive is malformed, or of an unknown version
+    InvalidArchive,
+    /// A root of the archive has no record
+    MissingRoot(Id),
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<CanonError> for Error {
+    fn from(e: CanonError) -> Self {
+        Error::Canon(e)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "io error: {}", e),
+            Error::Canon(e) => write!(f, "canon error: {:?}", e),
+            Error::InvalidArchive => write!(f, "invalid archive"),
+            Error::MissingRoot(id) => write!(f, "missing root {}", id),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+fn eof() -> io::Error {
+    io::Error::from(io::ErrorKind::UnexpectedEof)
+}
+
+fn write_id<W: Write>(writer: &mut W, id: &Id) -> io::Result<()> {
+    let mut bytes = [0u8; ID_BYTES];
+    bytes[0] = id.version;
+    bytes[1..5].copy_from_slice(&id.len.to_le_bytes());
+    bytes[5..].copy_from_slice(&id.hash);
+    writer.write_all(&bytes)
+}
+
+// Reads an id, returning `None` at the end of the archive
+fn read_id<R: Read>(reader: &mut R) -> Result<Option<Id>, Error> {
+    let mut bytes = [0u8; ID_BYTES];
+    let mut read = 0;
+    while read < ID_BYTES {
+        match reader.read(&mut bytes[read..]) {
+            Ok(0) if read == 0 => return Ok(None),
+            Ok(0) => return Err(eof().into()),
+            Ok(n) => read += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let mut len = [0u8; 4];
+    len.copy_from_slice(&bytes[1..5]);
+    let mut hash = IdHash::default();
+    hash.copy_from_slice(&bytes[5..]);
+
+    Ok(Some(Id::from_parts(
+        bytes[0],
+        u32::from_le_bytes(len),
+        hash,
+    )?))
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/// Writes the entries reachable from `roots` in `store` as an archive
+///
+/// The entries are visited depth first by a `Walk`, with `children` returning
+/// the `Id`s each entry refers to. For graphs of values of a single type `T`
+/// implementing `VisitIds`, this is `children_of::<T>`, which decodes every
+/// entry as a `T`. Archives of graphs mixing types need a `children`
+/// function that knows the type of each entry. Every entry is written once,
+/// in the order it is visited.
+///
+/// Returns the number of records written.
+pub fn export<W, F>(
+    store: &StoreRef,
+    roots: &[Id],
+    children: F,
+    mut writer: W,
+) -> Result<usize, Error>
+where
+    W: Write,
+    F: FnMut(&Id, &[u8]) -> Result<Vec<Id>, CanonError>,
+{
+    writer.write_all(&MAGIC)?;
+    writer.write_all(&[VERSION])?;
+    writer.write_all(&(roots.len() as u32).to_le_bytes())?;
+    for root in roots {
+        write_id(&mut writer, root)?;
+    }
+
+    let records = Store::scoped(store.clone(), || {
+        let mut records = 0;
+        for item in Walk::new(roots, Order::DepthFirst, children) {
+            let (id, bytes) = item?;
+            if id.inline_bytes().is_none() {
+                write_id(&mut writer, &id)?;
+                writer.write_all(&bytes)?;
+                records += 1;
+            }
+        }
+        Ok::<_, Error>(records)
+    })?;
+
+    writer.flush()?;
+    Ok(records)
+}
+
+/// Reader of the records of an archive
+///
+/// Yields the `Id` and bytes of each record in order, without checking the
+/// bytes against the `Id`. Iteration ends after the first error.
+#[derive(Debug)]
+pub struct Reader<R> {
+    reader: R,
+    roots: Vec<Id>,
+    done: bool,
+}
+
+impl<R> Reader<R>
+where
+    R: Read,
+{
+    /// Reads the header of an archive
+    pub fn new(mut reader: R) -> Result<Self, Error> {
+        let mut magic = [0u8; 8];
+        let mut version = [0u8; 1];
+        reader.read_exact(&mut magic)?;
+        reader.read_exact(&mut version)?;
+        if magic != MAGIC || version[0] != VERSION {
+            return Err(Error::InvalidArchive);
+        }
+
+        let count = read_u32(&mut reader)?;
+        let mut roots = Vec::new();
+        for _ in 0..count {
+            let root = read_id(&mut reader)?.ok_or(Error::InvalidArchive)?;
+            roots.push(root);
+        }
+
+        Ok(Reader {
+            reader,
+            roots,
+            done: false,
+        })
+    }
+
+    /// Returns the roots of the archive
+    pub fn roots(&self) -> &[Id] {
+        &self.roots
+    }
+
+    fn read_record(&mut self) -> Result<Option<(Id, Vec<u8>)>, Error> {
+        let id = match read_id(&mut self.reader)? {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+        if id.inline_bytes().is_some() {
+            return Err(Error::InvalidArchive);
+        }
+        // the length is not trusted with an allocation up front
+        let mut bytes = Vec::new();
+        (&mut self.reader)
+            .take(id.size() as u64)
+            .read_to_end(&mut bytes)?;
+        if bytes.len() != id.size() {
+            return Err(eof().into());
+        }
+        Ok(Some((id, bytes)))
+    }
+}
+
+impl<R> Iterator for Reader<R>
+where
+    R: Read,
+{
+    type Item = Result<(Id, Vec<u8>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let record = self.read_record().transpose();
+        self.done = !matches!(record, Some(Ok(_)));
+        record
+    }
+}
+
+/// Reads an archive into `store`, returning its roots
+///
+/// The bytes of each record are checked against the hash of its `Id` before
+/// being stored, failing with `CanonError::Corrupted` on a mismatch. Records
+/// keep the hash they were archived with, whatever the hash configuration of
+/// the store.
+///
+/// Fails with `Error::MissingRoot` if a root that is not inline has no
+/// record. Records read before an error are kept in the store.
+pub fn import<R>(store: &StoreRef, reader: R) -> Result<Vec<Id>, Error>
+where
+    R: Read,
+{
+    let mut reader = Reader::new(reader)?;
+    let mut imported = BTreeSet::new();
+    for record in &mut reader {
+        let (id, bytes) = record?;
+        store.insert(&id, &bytes)?;
+        imported.insert(id.hash);
+    }
+
+    let missing = reader.roots.iter().find(|root| {
+        root.inline_bytes().is_none() && !imported.contains(&root.hash)
+    });
+    match missing {
+        Some(root) => Err(Error::MissingRoot(*root)),
+        None => Ok(reader.roots),
+    }
+}
@@ -15,6 +15,8 @@ pub enum CanonError {
     InvalidEncoding,
     /// The instance could not be found in storage
     NotFound,
+    /// The stored bytes do not match the length or hash of their `Id`
+    Corrupted,
 }
 
 impl Canon for CanonError {
@@ -22,6 +24,7 @@ impl Canon for CanonError {
         let byte = match self {
             CanonError::InvalidEncoding => 0,
             CanonError::NotFound => 1,
+            CanonError::Corrupted => 2,
         };
         sink.copy_bytes(&[byte])
     }
@@ -30,6 +33,7 @@ impl Canon for CanonError {
         match u8::decode(source)? {
             0 => Ok(CanonError::InvalidEncoding),
             1 => Ok(CanonError::NotFound),
+            2 => Ok(CanonError::Corrupted),
             _ => Err(CanonError::InvalidEncoding),
         }
     }
@@ -67,4 +71,37 @@ pub trait Canon: Sized + Clone {
     fn decode(source: &mut Source) -> Result<Self, CanonError>;
     /// Returns the number of bytes needed to encode this value
     fn encoded_len(&self) -> usize;
+
+    /// Write a slice of values as bytes to a `Sink`
+    ///
+    /// Encodes each value in turn by default. Types whose encoding is their
+    /// in-memory representation override this, along with the other slice
+    /// methods, to copy the whole slice at once.
+    fn encode_slice(slice: &[Self], sink: &mut Sink) {
+        slice.iter().for_each(|item| item.encode(sink));
+    }
+
+    /// Return `len` decoded values from bytes in a `Source`
+    fn decode_vec(
+        source: &mut Source,
+        len: usize,
+    ) -> Result<Vec<Self>, CanonError> {
+        let mut vec = Vec::new();
+        for _ in 0..len {
+            vec.push(Self::decode(source)?);
+        }
+        Ok(vec)
+    }
+
+    /// Return an array of decoded values from bytes in a `Source`
+    fn decode_array<const N: usize>(
+        source: &mut Source,
+    ) -> Result<[Self; N], CanonError> {
+        array_init::try_array_init(|_| Self::decode(source))
+    }
+
+    /// Returns the number of bytes needed to encode a slice of values
+    fn encoded_len_slice(slice: &[Self]) -> usize {
+        slice.iter().fold(0, |len, item| len + item.encoded_len())
+    }
 }
@@ -0,0 +1,293 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! `Canon` implementations for types of external crates, each behind a
+//! feature named after the crate.
+//!
+//! Sequences are encoded like a `Vec`, and maps like a `BTreeMap`.
+
+#[cfg(feature = "bytes")]
+mod bytes_impls {
+    use alloc::boxed::Box;
+    use core::convert::TryFrom;
+
+    use bytes::Bytes;
+
+    use crate::{
+        Canon, CanonError, CanonSchema, Id, Schema, Sink, Source, VisitIds,
+    };
+
+    impl Canon for Bytes {
+        fn encode(&self, sink: &mut Sink) {
+            (self.len() as u64).encode(sink);
+            sink.copy_bytes(self);
+        }
+
+        fn decode(source: &mut Source) -> Result<Self, CanonError> {
+            let len = u64::decode(source)?;
+            let len = usize::try_from(len)
+                .map_err(|_| CanonError::InvalidEncoding)?;
+            Ok(Bytes::copy_from_slice(source.read_bytes(len)))
+        }
+
+        fn encoded_len(&self) -> usize {
+            (self.len() as u64).encoded_len() + self.len()
+        }
+    }
+
+    impl CanonSchema for Bytes {
+        fn schema() -> Schema {
+            Schema::Sequence(Box::new(Schema::U8))
+        }
+    }
+
+    impl VisitIds for Bytes {
+        fn visit_ids(&self, _: &mut dyn FnMut(&Id)) {}
+    }
+}
+
+#[cfg(feature = "smallvec")]
+mod smallvec_impls {
+    use alloc::boxed::Box;
+    use core::convert::TryFrom;
+
+    use smallvec::{Array, SmallVec};
+
+    use crate::{
+        Canon, CanonError, CanonSchema, Id, Schema, Sink, Source, VisitIds,
+    };
+
+    impl<A> Canon for SmallVec<A>
+    where
+        A: Array,
+        A::Item: Canon,
+    {
+        fn encode(&self, sink: &mut Sink) {
+            (self.len() as u64).encode(sink);
+            A::Item::encode_slice(self, sink);
+        }
+
+        fn decode(source: &mut Source) -> Result<Self, CanonError> {
+            let len = u64::decode(source)?;
+            let len = usize::try_from(len)
+                .map_err(|_| CanonError::InvalidEncoding)?;
+            A::Item::decode_vec(source, len).map(SmallVec::from_vec)
+        }
+
+        fn encoded_len(&self) -> usize {
+            (self.len() as u64).encoded_len() + A::Item::encoded_len_slice(self)
+        }
+    }
+
+    impl<A> CanonSchema for SmallVec<A>
+    where
+        A: Array,
+        A::Item: CanonSchema,
+    {
+        fn schema() -> Schema {
+            Schema::Sequence(Box::new(A::Item::schema()))
+        }
+    }
+
+    impl<A> VisitIds for SmallVec<A>
+    where
+        A: Array,
+        A::Item: VisitIds,
+    {
+        fn visit_ids(&self, visit: &mut dyn FnMut(&Id)) {
+            self.iter().for_each(|t| t.visit_ids(visit));
+        }
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+mod arrayvec_impls {
+    use alloc::boxed::Box;
+
+    use arrayvec::ArrayVec;
+
+    use crate::{
+        Canon, CanonError, CanonSchema, Id, Schema, Sink, Source, VisitIds,
+    };
+
+    impl<T, const CAP: usize> Canon for ArrayVec<T, CAP>
+    where
+        T: Canon,
+    {
+        fn encode(&self, sink: &mut Sink) {
+            (self.len() as u64).encode(sink);
+            T::encode_slice(self, sink);
+        }
+
+        fn decode(source: &mut Source) -> Result<Self, CanonError> {
+            let len = u64::decode(source)?;
+            if len > CAP as u64 {
+                return Err(CanonError::InvalidEncoding);
+            }
+            let mut vec = ArrayVec::new();
+            for _ in 0..len {
+                vec.push(T::decode(source)?);
+            }
+            Ok(vec)
+        }
+
+        fn encoded_len(&self) -> usize {
+            (self.len() as u64).encoded_len() + T::encoded_len_slice(self)
+        }
+    }
+
+    impl<T, const CAP: usize> CanonSchema for ArrayVec<T, CAP>
+    where
+        T: CanonSchema,
+    {
+        fn schema() -> Schema {
+            Schema::Sequence(Box::new(T::schema()))
+        }
+    }
+
+    impl<T, const CAP: usize> VisitIds for ArrayVec<T, CAP>
+    where
+        T: VisitIds,
+    {
+        fn visit_ids(&self, visit: &mut dyn FnMut(&Id)) {
+            self.iter().for_each(|t| t.visit_ids(visit));
+        }
+    }
+}
+
+#[cfg(feature = "heapless")]
+mod heapless_impls {
+    use alloc::boxed::Box;
+
+    use heapless::Vec;
+
+    use crate::{
+        Canon, CanonError, CanonSchema, Id, Schema, Sink, Source, VisitIds,
+    };
+
+    impl<T, const N: usize> Canon for Vec<T, N>
+    where
+        T: Canon,
+    {
+        fn encode(&self, sink: &mut Sink) {
+            (self.len() as u64).encode(sink);
+            T::encode_slice(self, sink);
+        }
+
+        fn decode(source: &mut Source) -> Result<Self, CanonError> {
+            let len = u64::decode(source)?;
+            if len > N as u64 {
+                return Err(CanonError::InvalidEncoding);
+            }
+            let mut vec = Vec::new();
+            for _ in 0..len {
+                vec.push(T::decode(source)?)
+                    .map_err(|_| CanonError::InvalidEncoding)?;
+            }
+            Ok(vec)
+        }
+
+        fn encoded_len(&self) -> usize {
+            (self.len() as u64).encoded_len() + T::encoded_len_slice(self)
+        }
+    }
+
+    impl<T, const N: usize> CanonSchema for Vec<T, N>
+    where
+        T: CanonSchema,
+    {
+        fn schema() -> Schema {
+            Schema::Sequence(Box::new(T::schema()))
+        }
+    }
+
+    impl<T, const N: usize> VisitIds for Vec<T, N>
+    where
+        T: VisitIds,
+    {
+        fn visit_ids(&self, visit: &mut dyn FnMut(&Id)) {
+            self.iter().for_each(|t| t.visit_ids(visit));
+        }
+    }
+}
+
+#[cfg(feature = "hashbrown")]
+mod hashbrown_impls {
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+    use core::hash::{BuildHasher, Hash};
+
+    use hashbrown::HashMap;
+
+    use crate::{
+        Canon, CanonError, CanonSchema, Id, Schema, Sink, Source, VisitIds,
+    };
+
+    // The entries are encoded in key order, since the iteration order of a
+    // hash map would give the same map many encodings. This makes the
+    // encoding identical to that of a `BTreeMap` with the same entries.
+    impl<K, V, S> Canon for HashMap<K, V, S>
+    where
+        K: Ord + Hash + Canon,
+        V: Canon,
+        S: BuildHasher + Default + Clone,
+    {
+        fn encode(&self, sink: &mut Sink) {
+            let mut entries: Vec<_> = self.iter().collect();
+            entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+
+            (self.len() as u64).encode(sink);
+            entries.iter().for_each(|(k, v)| {
+                k.encode(sink);
+                v.encode(sink);
+            });
+        }
+
+        fn decode(source: &mut Source) -> Result<Self, CanonError> {
+            let len = u64::decode(source)?;
+            let mut map = HashMap::default();
+            for _ in 0..len {
+                let key = K::decode(source)?;
+                let value = V::decode(source)?;
+                map.insert(key, value);
+            }
+            Ok(map)
+        }
+
+        fn encoded_len(&self) -> usize {
+            let len = (self.len() as u64).encoded_len();
+            self.iter().fold(len, |len, (k, v)| {
+                len + k.encoded_len() + v.encoded_len()
+            })
+        }
+    }
+
+    impl<K, V, S> CanonSchema for HashMap<K, V, S>
+    where
+        K: CanonSchema,
+        V: CanonSchema,
+    {
+        fn schema() -> Schema {
+            Schema::Map(Box::new(K::schema()), Box::new(V::schema()))
+        }
+    }
+
+    impl<K, V, S> VisitIds for HashMap<K, V, S>
+    where
+        K: Ord + VisitIds,
+        V: VisitIds,
+    {
+        fn visit_ids(&self, visit: &mut dyn FnMut(&Id)) {
+            let mut entries: Vec<_> = self.iter().collect();
+            entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+
+            entries.iter().for_each(|(k, v)| {
+                k.visit_ids(visit);
+                v.visit_ids(visit);
+            });
+        }
+    }
+}
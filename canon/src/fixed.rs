@@ -0,0 +1,114 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use core::mem;
+
+use crate::{Canon, CanonError, Sink, Source};
+
+/// An integer encoded at its full width in little endian byte order, instead
+/// of as a varint.
+///
+/// Every value of the type occupies the same number of bytes, which allows
+/// patching encoded values in place and reading records at fixed offsets.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FixedLE<T>(pub T);
+
+/// An integer encoded at its full width in big endian byte order, instead of
+/// as a varint.
+///
+/// For unsigned integers the encoded bytes sort in the same order as the
+/// values, which allows binary searching over encoded records.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FixedBE<T>(pub T);
+
+macro_rules! fixed {
+    ($int:ty) => {
+        impl Canon for FixedLE<$int> {
+            fn encode(&self, sink: &mut Sink) {
+                sink.copy_bytes(&self.0.to_le_bytes())
+            }
+
+            fn decode(source: &mut Source) -> Result<Self, CanonError> {
+                const LEN: usize = mem::size_of::<$int>();
+                let mut bytes = [0u8; LEN];
+                bytes.copy_from_slice(source.read_bytes(LEN));
+                Ok(FixedLE(<$int>::from_le_bytes(bytes)))
+            }
+
+            fn encoded_len(&self) -> usize {
+                mem::size_of::<$int>()
+            }
+        }
+
+        impl Canon for FixedBE<$int> {
+            fn encode(&self, sink: &mut Sink) {
+                sink.copy_bytes(&self.0.to_be_bytes())
+            }
+
+            fn decode(source: &mut Source) -> Result<Self, CanonError> {
+                const LEN: usize = mem::size_of::<$int>();
+                let mut bytes = [0u8; LEN];
+                bytes.copy_from_slice(source.read_bytes(LEN));
+                Ok(FixedBE(<$int>::from_be_bytes(bytes)))
+            }
+
+            fn encoded_len(&self) -> usize {
+                mem::size_of::<$int>()
+            }
+        }
+
+        impl From<$int> for FixedLE<$int> {
+            fn from(int: $int) -> Self {
+                FixedLE(int)
+            }
+        }
+
+        impl From<$int> for FixedBE<$int> {
+            fn from(int: $int) -> Self {
+                FixedBE(int)
+            }
+        }
+    };
+}
+
+fixed!(u8);
+fixed!(i8);
+
+fixed!(u16);
+fixed!(i16);
+
+fixed!(u32);
+fixed!(i32);
+
+fixed!(u64);
+fixed!(i64);
+
+fixed!(u128);
+fixed!(i128);
+
+#[cfg(not(target_arch = "wasm32"))]
+mod impl_arbitrary {
+    use super::*;
+    use arbitrary::{Arbitrary, Result, Unstructured};
+
+    impl<'a, T> Arbitrary<'a> for FixedLE<T>
+    where
+        T: Arbitrary<'a>,
+    {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            T::arbitrary(u).map(FixedLE)
+        }
+    }
+
+    impl<'a, T> Arbitrary<'a> for FixedBE<T>
+    where
+        T: Arbitrary<'a>,
+    {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            T::arbitrary(u).map(FixedBE)
+        }
+    }
+}
@@ -0,0 +1,136 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use blake2b_simd::Params;
+
+use crate::id::{IdHash, IdVersion, HASH_BYTES};
+
+/// The hash functions available for computing an `IdHash`
+///
+/// Poseidon and other hashes over finite fields are not provided: they hash
+/// field elements rather than bytes, and the store has no canonical mapping
+/// of encoded values to field elements.
+///
+/// Which variants exist depends on the enabled features, so matches outside
+/// of this crate need a wildcard arm.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum HashAlgorithm {
+    /// BLAKE2b with a 32 byte output, the default
+    #[default]
+    Blake2b,
+    /// BLAKE3, available with the `blake3` feature
+    #[cfg(feature = "blake3")]
+    Blake3,
+    /// SHA-256, available with the `sha2` feature
+    #[cfg(feature = "sha2")]
+    Sha256,
+}
+
+/// Describes how the bytes of a value are hashed into an `IdHash`
+///
+/// A non-empty `domain` is used for domain separation: the hashed input is
+/// prefixed by the length of the domain as a little endian `u32`, followed
+/// by the domain itself. An empty domain leaves the input untouched, so the
+/// default configuration is plain BLAKE2b-256 over the bytes.
+///
+/// `Id`s record the configuration they were hashed with in their version
+/// byte: the algorithm for configurations without a domain, and the
+/// `domain_id` otherwise. The store remembers every configuration with a
+/// domain it was set to by its `domain_id`, so `Id`s keep verifying after
+/// the configuration changes.
+///
+/// On the wasm bridge, the configuration is the one of the host, which
+/// reports its `Id` version through the `hash_version` import.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HashConfig {
+    /// The hash function to use
+    pub algorithm: HashAlgorithm,
+    /// The domain separation tag, empty for none
+    pub domain: &'static [u8],
+    /// Identifies the domain in the version of `Id`s, below 128
+    pub domain_id: u8,
+}
+
+impl HashConfig {
+    /// The default configuration, BLAKE2b-256 without a domain
+    pub const DEFAULT: HashConfig = HashConfig {
+        algorithm: HashAlgorithm::Blake2b,
+        domain: &[],
+        domain_id: 0,
+    };
+
+    /// Creates a configuration using `algorithm` without a domain
+    pub const fn new(algorithm: HashAlgorithm) -> Self {
+        HashConfig {
+            algorithm,
+            domain: &[],
+            domain_id: 0,
+        }
+    }
+
+    /// Returns the configuration with the domain separation tag set,
+    /// identified by `id` in the version of `Id`s
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not below 128.
+    pub const fn with_domain(mut self, id: u8, domain: &'static [u8]) -> Self {
+        assert!(id < 0x80, "domain ids are below 128");
+        self.domain = domain;
+        self.domain_id = id;
+        self
+    }
+
+    /// Returns the version of `Id`s hashed with the configuration
+    pub const fn id_version(&self) -> IdVersion {
+        match self.domain.is_empty() {
+            true => IdVersion::hashed(self.algorithm),
+            false => IdVersion::Domain(self.domain_id),
+        }
+    }
+
+    /// Hash a slice of bytes according to the configuration
+    pub fn hash(&self, bytes: &[u8]) -> IdHash {
+        let prefix = (self.domain.len() as u32).to_le_bytes();
+        let parts: &[&[u8]] = if self.domain.is_empty() {
+            &[bytes]
+        } else {
+            &[&prefix, self.domain, bytes]
+        };
+
+        let mut buf = [0u8; HASH_BYTES];
+        match self.algorithm {
+            HashAlgorithm::Blake2b => {
+                let mut state =
+                    Params::new().hash_length(HASH_BYTES).to_state();
+                for part in parts {
+                    state.update(part);
+                }
+                buf.copy_from_slice(state.finalize().as_ref());
+            }
+            #[cfg(feature = "blake3")]
+            HashAlgorithm::Blake3 => {
+                let mut hasher = blake3::Hasher::new();
+                for part in parts {
+                    hasher.update(part);
+                }
+                buf.copy_from_slice(hasher.finalize().as_bytes());
+            }
+            #[cfg(feature = "sha2")]
+            HashAlgorithm::Sha256 => {
+                use sha2::Digest;
+
+                let mut hasher = sha2::Sha256::new();
+                for part in parts {
+                    hasher.update(part);
+                }
+                buf.copy_from_slice(&hasher.finalize());
+            }
+        }
+        buf
+    }
+}
@@ -4,40 +4,138 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use alloc::vec;
 use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::str::FromStr;
 
 use crate::canon::{Canon, CanonError, EncodeToVec};
+use crate::hash::{HashAlgorithm, HashConfig};
+#[cfg(all(not(target_arch = "wasm32"), feature = "async"))]
+use crate::store::AsyncBackend;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::store::StoreRef;
 use crate::store::{Sink, Source, Store};
 
-const VERSION: u8 = 0;
-
 /// values
 pub const HASH_BYTES: usize = 32;
 
 /// A hash identifiying some data
 pub type IdHash = [u8; 32];
 
+/// The format of an `Id`, stored in its version byte
+///
+/// Hashed Ids refer to a value in the store by its hash, computed with the
+/// algorithm given by the version, or under a domain with the hash
+/// configuration of the domain id. Inline Ids hold values of up to
+/// `HASH_BYTES` bytes themselves, and never touch the store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum IdVersion {
+    /// A value stored by its BLAKE2b hash
+    Blake2b,
+    /// A value held inline
+    Inline,
+    /// A value stored by its BLAKE3 hash
+    Blake3,
+    /// A value stored by its SHA-256 hash
+    Sha256,
+    /// A value stored by its hash under a domain, with the hash configuration
+    /// of the given `domain_id`
+    Domain(u8),
+}
+
+impl IdVersion {
+    /// Returns the version byte
+    pub const fn byte(self) -> u8 {
+        match self {
+            IdVersion::Blake2b => 0,
+            IdVersion::Inline => 1,
+            IdVersion::Blake3 => 2,
+            IdVersion::Sha256 => 3,
+            IdVersion::Domain(id) => 0x80 | id,
+        }
+    }
+
+    /// Returns the version for a version byte, if it is known
+    pub const fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(IdVersion::Blake2b),
+            1 => Some(IdVersion::Inline),
+            2 => Some(IdVersion::Blake3),
+            3 => Some(IdVersion::Sha256),
+            0x80..=0xff => Some(IdVersion::Domain(byte & 0x7f)),
+            _ => None,
+        }
+    }
+
+    /// Returns the version of Ids hashed with `algorithm`
+    pub const fn hashed(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Blake2b => IdVersion::Blake2b,
+            #[cfg(feature = "blake3")]
+            HashAlgorithm::Blake3 => IdVersion::Blake3,
+            #[cfg(feature = "sha2")]
+            HashAlgorithm::Sha256 => IdVersion::Sha256,
+        }
+    }
+
+    /// Returns the hash algorithm of a hashed version
+    ///
+    /// Returns `None` for inline Ids, for algorithms not enabled by a
+    /// feature, and for domains, whose algorithm is given by the hash
+    /// configuration of the store.
+    pub const fn algorithm(self) -> Option<HashAlgorithm> {
+        match self {
+            IdVersion::Blake2b => Some(HashAlgorithm::Blake2b),
+            #[cfg(feature = "blake3")]
+            IdVersion::Blake3 => Some(HashAlgorithm::Blake3),
+            #[cfg(feature = "sha2")]
+            IdVersion::Sha256 => Some(HashAlgorithm::Sha256),
+            _ => None,
+        }
+    }
+}
+
+// Returns the length of the bytes following the version and length of an
+// encoded Id
+pub(crate) fn body_len(version: u8, len: u32) -> Result<usize, CanonError> {
+    match IdVersion::from_byte(version) {
+        Some(IdVersion::Inline) if len as usize <= HASH_BYTES => {
+            Ok(len as usize)
+        }
+        Some(IdVersion::Inline) | None => Err(CanonError::InvalidEncoding),
+        Some(_) => Ok(HASH_BYTES),
+    }
+}
+
 /// This is the Id type, that uniquely identifies slices of bytes,
 /// in rust equivalent to `&[u8]`. As in the case with `&[u8]` the length is
 /// also encoded in the type, making it a kind of a fat-pointer for content
 /// addressed byte-slices.
 ///
-/// The length of the corresponding byte-string is encoded in the first two
-/// bytes in big endian.
-///
-/// If the length of the byteslice is less than or equal to 32 bytes, the bytes
-/// are stored directly inline in the `bytes` field.
+/// The version byte determines the format of the Id, see `IdVersion`. It is
+/// followed by the length of the byte-string as a varint, and either a 32
+/// byte hash or, for inline Ids, the bytes themselves.
 ///
-/// Proposal: The trailing bytes in an inlined value MUST be set to zero
+/// The trailing bytes of the `hash` field of an inline Id are set to zero.
 #[derive(Hash, PartialEq, Eq, Default, Clone, Copy, PartialOrd, Ord)]
 pub struct Id {
-    version: u8,
-    len: u32,
-    hash: IdHash,
+    pub(crate) version: u8,
+    pub(crate) len: u32,
+    pub(crate) hash: IdHash,
 }
 
 impl core::fmt::Debug for Id {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if let Some(bytes) = self.inline_bytes() {
+            write!(f, "Id(inline ")?;
+            for byte in bytes {
+                write!(f, "{:02x}", byte)?;
+            }
+            return write!(f, ")");
+        }
         write!(f, "Id(")?;
         for byte in self.hash {
             write!(f, "{:02x}", byte)?;
@@ -48,30 +146,139 @@ impl core::fmt::Debug for Id {
 
 impl Id {
     /// Creates a new Id from a type
+    ///
+    /// The value is put in the store, and the Id gets the version of the
+    /// hash configuration used by the store.
     pub fn new<T>(t: &T) -> Self
     where
         T: Canon,
     {
         let bytes = t.encode_to_vec();
-        let len = bytes.len();
-        let hash = Store::put(&bytes);
+        Self::put(&bytes)
+    }
+
+    /// Creates new Ids for a slice of values, putting them in the store at
+    /// once
+    pub fn new_many<T>(values: &[T]) -> Vec<Self>
+    where
+        T: Canon,
+    {
+        let encoded: Vec<_> = values.iter().map(T::encode_to_vec).collect();
+        let blobs: Vec<&[u8]> = encoded.iter().map(Vec::as_slice).collect();
+
+        Store::put_many_versioned(&blobs)
+            .into_iter()
+            .zip(&encoded)
+            .map(|((hash, version), bytes)| Id {
+                version: version.byte(),
+                len: bytes.len() as u32,
+                hash,
+            })
+            .collect()
+    }
 
+    /// Creates a new Id from a type, putting it in the given store
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new_in<T>(t: &T, store: &StoreRef) -> Self
+    where
+        T: Canon,
+    {
+        Store::scoped(store.clone(), || Self::new(t))
+    }
+
+    /// Creates a new Id from a type, putting it in the given asynchronous
+    /// store
+    ///
+    /// The value is hashed with the hash configuration of `Store`.
+    #[cfg(all(not(target_arch = "wasm32"), feature = "async"))]
+    pub async fn new_async<T, B>(t: &T, store: &B) -> Self
+    where
+        T: Canon,
+        B: AsyncBackend,
+    {
+        let bytes = t.encode_to_vec();
+        let (hash, version) = Store::hash_versioned(&bytes);
+        store.put(hash, &bytes).await;
         Id {
-            version: VERSION,
-            len: (len as u32),
+            version: version.byte(),
+            len: bytes.len() as u32,
             hash,
         }
     }
 
+    /// Creates a new inline Id from a type, holding the encoded value itself
+    ///
+    /// Returns `None` if the value encodes to more than `HASH_BYTES` bytes.
+    pub fn new_inline<T>(t: &T) -> Option<Self>
+    where
+        T: Canon,
+    {
+        let len = t.encoded_len();
+        if len > HASH_BYTES {
+            return None;
+        }
+
+        let mut hash = IdHash::default();
+        t.encode(&mut Sink::new(&mut hash[..len]));
+
+        Some(Id {
+            version: IdVersion::Inline.byte(),
+            len: len as u32,
+            hash,
+        })
+    }
+
     /// Creates a new Id from raw data
+    ///
+    /// The Id gets the version of the hash configuration used by the store.
     pub fn raw(hash: [u8; 32], len: u32) -> Self {
         Id {
-            version: VERSION,
-            len: (len as u32),
+            version: Store::id_version().byte(),
+            len,
+            hash,
+        }
+    }
+
+    // Creates an Id from its fields, checking that they are consistent
+    pub(crate) fn from_parts(
+        version: u8,
+        len: u32,
+        hash: IdHash,
+    ) -> Result<Self, CanonError> {
+        let body = body_len(version, len)?;
+        if version == IdVersion::Inline.byte()
+            && hash[body..].iter().any(|b| *b != 0)
+        {
+            return Err(CanonError::InvalidEncoding);
+        }
+        Ok(Id { version, len, hash })
+    }
+
+    // The hash and the version come from the same hash configuration, even
+    // if it is changed concurrently
+    fn put(bytes: &[u8]) -> Self {
+        let (hash, version) = Store::put_versioned(bytes);
+        Id {
+            version: version.byte(),
+            len: bytes.len() as u32,
             hash,
         }
     }
 
+    /// Returns the version of the Id
+    pub fn version(&self) -> IdVersion {
+        IdVersion::from_byte(self.version)
+            .expect("ids are constructed with a known version")
+    }
+
+    /// Returns the bytes held by an inline Id, or `None` for a hashed one
+    pub fn inline_bytes(&self) -> Option<&[u8]> {
+        match self.version() {
+            IdVersion::Inline => Some(&self.hash[..self.size()]),
+            _ => None,
+        }
+    }
+
     /// Returns the computed hash of the value.
     ///
     /// Note that this is different from the payload itself in case of an
@@ -80,7 +287,10 @@ impl Id {
     /// Useful for giving a well-distributed unique id for all `Canon` types,
     /// for use in hash maps for example.
     pub fn hash(&self) -> IdHash {
-        self.hash
+        match self.inline_bytes() {
+            Some(bytes) => Store::hash(bytes),
+            None => self.hash,
+        }
     }
 
     /// Returns the length of the represented data
@@ -89,27 +299,117 @@ impl Id {
     }
 
     /// Attempts to reify the Id as an instance of type `T`
+    ///
+    /// Returns `CanonError::Corrupted` if the stored bytes do not have the
+    /// length of the Id.
     pub fn reify<T>(&self) -> Result<T, CanonError>
     where
         T: Canon,
     {
-        let len = self.size();
+        let buf = self.fetch_bytes()?;
+        T::decode(&mut Source::new(&buf))
+    }
 
-        let mut buf = Vec::new();
+    /// Attempts to reify the Id as an instance of type `T` from the given
+    /// store
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn reify_in<T>(&self, store: &StoreRef) -> Result<T, CanonError>
+    where
+        T: Canon,
+    {
+        Store::scoped(store.clone(), || self.reify())
+    }
 
-        buf.resize_with(len, || 0);
+    /// Attempts to reify the Id as an instance of type `T` from the given
+    /// asynchronous store
+    ///
+    /// Inline Ids are reified without accessing the store.
+    #[cfg(all(not(target_arch = "wasm32"), feature = "async"))]
+    pub async fn reify_async<T, B>(&self, store: &B) -> Result<T, CanonError>
+    where
+        T: Canon,
+        B: AsyncBackend,
+    {
+        let buf = match self.inline_bytes() {
+            Some(bytes) => bytes.to_vec(),
+            None => {
+                let mut buf = vec![0u8; self.size()];
+                store.get(&self.hash, &mut buf).await?;
+                buf
+            }
+        };
+        T::decode(&mut Source::new(&buf))
+    }
 
-        Store::get(&self.hash(), &mut buf)?;
-        let mut source = Source::new(&buf);
+    /// Attempts to reify the Id as an instance of type `T`, additionally
+    /// checking that the stored bytes hash to the hash of the Id
+    ///
+    /// Returns `CanonError::Corrupted` if the length or the hash of the
+    /// stored bytes do not match, and `CanonError::InvalidEncoding` if the
+    /// hash algorithm or domain of the Id is not available.
+    pub fn reify_checked<T>(&self) -> Result<T, CanonError>
+    where
+        T: Canon,
+    {
+        let buf = self.fetch_bytes()?;
+        self.verify(&buf)?;
+        T::decode(&mut Source::new(&buf))
+    }
+
+    /// Checks that `bytes` match the length and hash of the `Id`
+    ///
+    /// Returns `CanonError::Corrupted` on a mismatch, and
+    /// `CanonError::InvalidEncoding` if the hash configuration of the `Id` is
+    /// unknown. Inline `Id`s hold their bytes, and always match.
+    pub fn verify(&self, bytes: &[u8]) -> Result<(), CanonError> {
+        if self.inline_bytes().is_some() {
+            return Ok(());
+        }
+        if bytes.len() != self.size() {
+            return Err(CanonError::Corrupted);
+        }
+        let hash = Store::hash_with(self.version(), bytes)
+            .ok_or(CanonError::InvalidEncoding)?;
+        match hash == self.hash {
+            true => Ok(()),
+            false => Err(CanonError::Corrupted),
+        }
+    }
 
-        T::decode(&mut source)
+    pub(crate) fn fetch_bytes(&self) -> Result<Vec<u8>, CanonError> {
+        if let Some(bytes) = self.inline_bytes() {
+            return Ok(bytes.to_vec());
+        }
+        let mut buf = vec![0u8; self.size()];
+        Store::get(&self.hash, &mut buf)?;
+        Ok(buf)
     }
 
     /// Takes the bytes corresponding to this id out of the underlying store.
     ///
     /// If the Id is inlined, this is a no-op and returns `Ok(None)`
     pub fn take_bytes(&self) -> Result<Option<Vec<u8>>, CanonError> {
-        Ok(Some(Store::take_bytes(self)?))
+        match self.inline_bytes() {
+            Some(_) => Ok(None),
+            None => Ok(Some(Store::take_bytes(self)?)),
+        }
+    }
+
+    /// Re-hashes the value of the Id with the current hash configuration of
+    /// the store, returning the new Id
+    ///
+    /// Use this to migrate inline Ids, or Ids hashed with another algorithm
+    /// or domain, to the current configuration of the store. The old entry is
+    /// left in place.
+    pub fn rehash(&self) -> Result<Self, CanonError> {
+        let bytes = self.fetch_bytes()?;
+        Ok(Self::put(&bytes))
+    }
+
+    /// Returns `true` if the Id is hashed with the current hash configuration
+    /// of the store, and so needs no migration through `rehash`
+    pub fn is_current(&self) -> bool {
+        self.version() == Store::id_version()
     }
 }
 
@@ -117,26 +417,251 @@ impl Canon for Id {
     fn encode(&self, sink: &mut Sink) {
         self.version.encode(sink);
         self.len.encode(sink);
-        sink.copy_bytes(&self.hash());
+        match self.inline_bytes() {
+            Some(bytes) => sink.copy_bytes(bytes),
+            None => sink.copy_bytes(&self.hash),
+        }
     }
 
     fn decode(source: &mut Source) -> Result<Self, CanonError> {
         let version = u8::decode(source)?;
+        let len = u32::decode(source)?;
+        let body = body_len(version, len)?;
 
-        if version != 0 {
+        let mut hash = [0u8; HASH_BYTES];
+        hash[..body].copy_from_slice(source.read_bytes(body));
+
+        Ok(Id { version, len, hash })
+    }
+
+    fn encoded_len(&self) -> usize {
+        let body = match self.inline_bytes() {
+            Some(bytes) => bytes.len(),
+            None => HASH_BYTES,
+        };
+        1 + self.len.encoded_len() + body
+    }
+}
+
+// Length of the checksum appended to the string representation of an Id
+const CHECKSUM_BYTES: usize = 4;
+// Length of the bytes in the string representation of an Id
+const STR_BYTES: usize = 1 + 4 + HASH_BYTES + CHECKSUM_BYTES;
+
+impl Id {
+    // The bytes of the string representation: the version, the length as a
+    // big endian `u32`, the hash and a checksum of the preceding bytes.
+    fn str_bytes(&self) -> [u8; STR_BYTES] {
+        let mut bytes = [0u8; STR_BYTES];
+        bytes[0] = self.version;
+        bytes[1..5].copy_from_slice(&self.len.to_be_bytes());
+        bytes[5..5 + HASH_BYTES].copy_from_slice(&self.hash);
+
+        let checksum = checksum(&bytes[..STR_BYTES - CHECKSUM_BYTES]);
+        bytes[STR_BYTES - CHECKSUM_BYTES..].copy_from_slice(&checksum);
+        bytes
+    }
+}
+
+// Always BLAKE2b, independent of the hash configuration of the store
+fn checksum(bytes: &[u8]) -> [u8; CHECKSUM_BYTES] {
+    let mut checksum = [0u8; CHECKSUM_BYTES];
+    checksum
+        .copy_from_slice(&HashConfig::DEFAULT.hash(bytes)[..CHECKSUM_BYTES]);
+    checksum
+}
+
+fn hex_digit(c: u8) -> Result<u8, CanonError> {
+    (c as char)
+        .to_digit(16)
+        .map(|d| d as u8)
+        .ok_or(CanonError::InvalidEncoding)
+}
+
+/// Formats the Id as lowercase hex of its version, its length as a big
+/// endian `u32`, its hash and a four byte checksum.
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.str_bytes() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the format produced by `Display`, returning
+/// `CanonError::InvalidEncoding` on malformed input or a checksum mismatch.
+impl FromStr for Id {
+    type Err = CanonError;
+
+    fn from_str(s: &str) -> Result<Self, CanonError> {
+        let s = s.as_bytes();
+        if s.len() != STR_BYTES * 2 {
             return Err(CanonError::InvalidEncoding);
         }
 
-        let len = u32::decode(source)?;
-        let mut hash = [0u8; HASH_BYTES];
+        let mut bytes = [0u8; STR_BYTES];
+        for (byte, pair) in bytes.iter_mut().zip(s.chunks(2)) {
+            *byte = hex_digit(pair[0])? << 4 | hex_digit(pair[1])?;
+        }
 
-        hash[..].copy_from_slice(source.read_bytes(HASH_BYTES));
+        let mut len = [0u8; 4];
+        len.copy_from_slice(&bytes[1..5]);
+        let mut hash = IdHash::default();
+        hash.copy_from_slice(&bytes[5..5 + HASH_BYTES]);
 
-        Ok(Id { version, len, hash })
+        let id = Id::from_parts(bytes[0], u32::from_be_bytes(len), hash)?;
+
+        if id.str_bytes() != bytes {
+            return Err(CanonError::InvalidEncoding);
+        }
+        Ok(id)
+    }
+}
+
+/// An `Id` that remembers the type of the value it refers to
+///
+/// The encoding is identical to the one of `Id`, so the type information is
+/// purely a compile time aid, and a `TypedId<T>` can be freely converted to
+/// and from an untyped `Id`.
+pub struct TypedId<T> {
+    id: Id,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> TypedId<T> {
+    /// Creates a typed id from an untyped one, trusting that it refers to a
+    /// value of type `T`
+    pub const fn from_id(id: Id) -> Self {
+        TypedId {
+            id,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the untyped id
+    pub const fn id(&self) -> Id {
+        self.id
+    }
+
+    /// Returns the computed hash of the value
+    pub fn hash(&self) -> IdHash {
+        self.id.hash()
+    }
+
+    /// Returns the length of the represented data
+    pub const fn size(&self) -> usize {
+        self.id.size()
+    }
+}
+
+impl<T> TypedId<T>
+where
+    T: Canon,
+{
+    /// Creates a new typed id from a value
+    pub fn new(t: &T) -> Self {
+        Self::from_id(Id::new(t))
+    }
+
+    /// Attempts to reify the id as an instance of type `T`
+    pub fn reify(&self) -> Result<T, CanonError> {
+        self.id.reify()
+    }
+
+    /// Attempts to reify the id as an instance of type `T`, additionally
+    /// checking that the stored bytes hash to the hash of the id
+    pub fn reify_checked(&self) -> Result<T, CanonError> {
+        self.id.reify_checked()
+    }
+}
+
+impl<T> From<TypedId<T>> for Id {
+    fn from(typed: TypedId<T>) -> Self {
+        typed.id
+    }
+}
+
+impl<T> From<Id> for TypedId<T> {
+    fn from(id: Id) -> Self {
+        Self::from_id(id)
+    }
+}
+
+// The trait implementations are written out by hand, since deriving them
+// would require `T` to implement them as well.
+
+impl<T> Clone for TypedId<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for TypedId<T> {}
+
+impl<T> Default for TypedId<T> {
+    fn default() -> Self {
+        Self::from_id(Id::default())
+    }
+}
+
+impl<T> PartialEq for TypedId<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for TypedId<T> {}
+
+impl<T> PartialOrd for TypedId<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for TypedId<T> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+impl<T> Hash for TypedId<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(&self.id, state)
+    }
+}
+
+impl<T> fmt::Display for TypedId<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.id, f)
+    }
+}
+
+impl<T> FromStr for TypedId<T> {
+    type Err = CanonError;
+
+    fn from_str(s: &str) -> Result<Self, CanonError> {
+        Id::from_str(s).map(Self::from_id)
+    }
+}
+
+impl<T> fmt::Debug for TypedId<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Typed{:?}", self.id)
+    }
+}
+
+impl<T> Canon for TypedId<T> {
+    fn encode(&self, sink: &mut Sink) {
+        self.id.encode(sink)
+    }
+
+    fn decode(source: &mut Source) -> Result<Self, CanonError> {
+        Id::decode(source).map(Self::from_id)
     }
 
     fn encoded_len(&self) -> usize {
-        1 + self.len.encoded_len() + HASH_BYTES
+        self.id.encoded_len()
     }
 }
 
@@ -149,6 +674,13 @@ mod impl_arbitrary {
         fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
             let mut bytevec = Vec::arbitrary(u)?;
 
+            // randomly use an inline id, if the bytes fit
+            if bool::arbitrary(u)? {
+                if let Some(id) = Id::new_inline(&bytevec) {
+                    return Ok(id);
+                }
+            }
+
             // randomly extend by a hash length, to overflow inlined
             if bool::arbitrary(u)? {
                 let junk = Store::hash(&bytevec[..]);
@@ -158,4 +690,13 @@ mod impl_arbitrary {
             Ok(Id::new(&bytevec))
         }
     }
+
+    impl<'a, T> Arbitrary<'a> for TypedId<T>
+    where
+        T: Canon + Arbitrary<'a>,
+    {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            Ok(TypedId::new(&T::arbitrary(u)?))
+        }
+    }
 }
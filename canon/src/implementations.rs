@@ -5,10 +5,19 @@
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
 #![allow(clippy::empty_loop)]
+use core::cmp::{Ordering, Reverse};
 use core::marker::PhantomData;
 use core::mem;
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroU128, NonZeroU16,
+    NonZeroU32, NonZeroU64, NonZeroU8, Wrapping,
+};
+use core::ops::{Range, RangeInclusive};
+use core::time::Duration;
 use dusk_varint::VarInt;
 
+use alloc::vec::Vec;
+
 use crate::{Canon, CanonError, Sink, Source};
 
 impl Canon for u8 {
@@ -25,6 +34,29 @@ impl Canon for u8 {
     fn encoded_len(&self) -> usize {
         1
     }
+
+    fn encode_slice(slice: &[Self], sink: &mut Sink) {
+        sink.copy_bytes(slice)
+    }
+
+    fn decode_vec(
+        source: &mut Source,
+        len: usize,
+    ) -> Result<Vec<Self>, CanonError> {
+        Ok(source.read_bytes(len).into())
+    }
+
+    fn decode_array<const N: usize>(
+        source: &mut Source,
+    ) -> Result<[Self; N], CanonError> {
+        let mut bytes = [0u8; N];
+        bytes.copy_from_slice(source.read_bytes(N));
+        Ok(bytes)
+    }
+
+    fn encoded_len_slice(slice: &[Self]) -> usize {
+        slice.len()
+    }
 }
 
 macro_rules! varint {
@@ -239,6 +271,163 @@ impl<T> Canon for PhantomData<T> {
     }
 }
 
+macro_rules! nonzero {
+    ($nonzero:ty, $int:ty) => {
+        impl Canon for $nonzero {
+            fn encode(&self, sink: &mut Sink) {
+                self.get().encode(sink)
+            }
+
+            fn decode(source: &mut Source) -> Result<Self, CanonError> {
+                <$nonzero>::new(<$int>::decode(source)?)
+                    .ok_or(CanonError::InvalidEncoding)
+            }
+
+            fn encoded_len(&self) -> usize {
+                self.get().encoded_len()
+            }
+        }
+    };
+}
+
+nonzero!(NonZeroU8, u8);
+
+nonzero!(NonZeroU16, u16);
+nonzero!(NonZeroI16, i16);
+
+nonzero!(NonZeroU32, u32);
+nonzero!(NonZeroI32, i32);
+
+nonzero!(NonZeroU64, u64);
+nonzero!(NonZeroI64, i64);
+
+nonzero!(NonZeroU128, u128);
+nonzero!(NonZeroI128, i128);
+
+impl<T> Canon for Wrapping<T>
+where
+    T: Canon,
+{
+    fn encode(&self, sink: &mut Sink) {
+        self.0.encode(sink)
+    }
+
+    fn decode(source: &mut Source) -> Result<Self, CanonError> {
+        T::decode(source).map(Wrapping)
+    }
+
+    fn encoded_len(&self) -> usize {
+        self.0.encoded_len()
+    }
+}
+
+impl<T> Canon for Reverse<T>
+where
+    T: Canon,
+{
+    fn encode(&self, sink: &mut Sink) {
+        self.0.encode(sink)
+    }
+
+    fn decode(source: &mut Source) -> Result<Self, CanonError> {
+        T::decode(source).map(Reverse)
+    }
+
+    fn encoded_len(&self) -> usize {
+        self.0.encoded_len()
+    }
+}
+
+impl Canon for Ordering {
+    fn encode(&self, sink: &mut Sink) {
+        match self {
+            Ordering::Less => sink.copy_bytes(&[0]),
+            Ordering::Equal => sink.copy_bytes(&[1]),
+            Ordering::Greater => sink.copy_bytes(&[2]),
+        }
+    }
+
+    fn decode(source: &mut Source) -> Result<Self, CanonError> {
+        match source.read_bytes(1) {
+            [0] => Ok(Ordering::Less),
+            [1] => Ok(Ordering::Equal),
+            [2] => Ok(Ordering::Greater),
+            _ => Err(CanonError::InvalidEncoding),
+        }
+    }
+
+    fn encoded_len(&self) -> usize {
+        1
+    }
+}
+
+const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+impl Canon for Duration {
+    fn encode(&self, sink: &mut Sink) {
+        self.as_secs().encode(sink);
+        self.subsec_nanos().encode(sink);
+    }
+
+    fn decode(source: &mut Source) -> Result<Self, CanonError> {
+        let secs = u64::decode(source)?;
+        let nanos = u32::decode(source)?;
+
+        // `Duration::new` would carry the excess nanoseconds over into the
+        // seconds, giving two encodings for the same value
+        if nanos >= NANOS_PER_SEC {
+            return Err(CanonError::InvalidEncoding);
+        }
+
+        Ok(Duration::new(secs, nanos))
+    }
+
+    fn encoded_len(&self) -> usize {
+        self.as_secs().encoded_len() + self.subsec_nanos().encoded_len()
+    }
+}
+
+impl<T> Canon for Range<T>
+where
+    T: Canon,
+{
+    fn encode(&self, sink: &mut Sink) {
+        self.start.encode(sink);
+        self.end.encode(sink);
+    }
+
+    fn decode(source: &mut Source) -> Result<Self, CanonError> {
+        let start = T::decode(source)?;
+        let end = T::decode(source)?;
+        Ok(start..end)
+    }
+
+    fn encoded_len(&self) -> usize {
+        self.start.encoded_len() + self.end.encoded_len()
+    }
+}
+
+// Only the bounds are encoded, an exhausted range decodes as a fresh one
+impl<T> Canon for RangeInclusive<T>
+where
+    T: Canon,
+{
+    fn encode(&self, sink: &mut Sink) {
+        self.start().encode(sink);
+        self.end().encode(sink);
+    }
+
+    fn decode(source: &mut Source) -> Result<Self, CanonError> {
+        let start = T::decode(source)?;
+        let end = T::decode(source)?;
+        Ok(start..=end)
+    }
+
+    fn encoded_len(&self) -> usize {
+        self.start().encoded_len() + self.end().encoded_len()
+    }
+}
+
 macro_rules! tuple {
     ( $($name:ident)+) => (
         #[allow(non_snake_case)]
@@ -261,6 +450,7 @@ macro_rules! tuple {
     );
 }
 
+tuple! { A }
 tuple! { A B }
 tuple! { A B C }
 tuple! { A B C D }
@@ -282,15 +472,15 @@ where
     T: Canon + Sized,
 {
     fn encode(&self, sink: &mut Sink) {
-        self.iter().for_each(|item| item.encode(sink));
+        T::encode_slice(self, sink)
     }
 
     fn decode(source: &mut Source) -> Result<Self, CanonError> {
-        array_init::try_array_init(|_| T::decode(source))
+        T::decode_array(source)
     }
 
     fn encoded_len(&self) -> usize {
-        self.iter().fold(0, |len, item| len + item.encoded_len())
+        T::encoded_len_slice(self)
     }
 }
 
@@ -299,6 +489,8 @@ mod alloc_impls {
 
     extern crate alloc;
 
+    use core::convert::TryFrom;
+
     use alloc::collections::{BTreeMap, BTreeSet};
     use alloc::rc::Rc;
     use alloc::string::String;
@@ -309,27 +501,20 @@ mod alloc_impls {
         fn encode(&self, sink: &mut Sink) {
             let len = self.len() as u64;
             len.encode(sink);
-            for t in self.iter() {
-                t.encode(sink);
-            }
+            T::encode_slice(self, sink);
         }
 
         fn decode(source: &mut Source) -> Result<Self, CanonError> {
-            let mut vec = Vec::new();
             let len = u64::decode(source)?;
-            for _ in 0..len {
-                vec.push(T::decode(source)?);
-            }
-            Ok(vec)
+            let len = usize::try_from(len)
+                .map_err(|_| CanonError::InvalidEncoding)?;
+            T::decode_vec(source, len)
         }
 
         fn encoded_len(&self) -> usize {
             // length of length
-            let mut len = (self.len() as u64).encoded_len();
-            for t in self.iter() {
-                len += t.encoded_len()
-            }
-            len
+            let len = (self.len() as u64).encoded_len();
+            len + T::encoded_len_slice(self)
         }
     }
 
@@ -398,13 +583,15 @@ mod alloc_impls {
 
         fn decode(source: &mut Source) -> Result<Self, CanonError> {
             let len = u64::decode(source)?;
-            let vec: Vec<u8> = source.read_bytes(len as usize).into();
+            let len = usize::try_from(len)
+                .map_err(|_| CanonError::InvalidEncoding)?;
+            let vec: Vec<u8> = source.read_bytes(len).into();
             String::from_utf8(vec).map_err(|_| CanonError::InvalidEncoding)
         }
 
         fn encoded_len(&self) -> usize {
             let len = self.len() as u64;
-            len.encoded_len() + self.as_bytes().len()
+            len.encoded_len() + self.len()
         }
     }
 
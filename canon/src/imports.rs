@@ -0,0 +1,324 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Host-side implementation of the `canon` wasm import module
+//!
+//! Each function implements the import of the same name, as documented in
+//! the `abi` module, backed by the `Store`. The functions are independent of
+//! the wasm engine: they access the linear memory of the calling module
+//! through the `Memory` trait, and take and return the `i32` values of the
+//! import. Embedders register them with their engine, wrapping the linear
+//! memory of the caller.
+//!
+//! Modules built against version 0 of the ABI expect `put_legacy`,
+//! `get_legacy` and `hash_legacy` as their `put`, `get` and `hash` imports.
+//! Embedders should call the `abi::VERSION_EXPORT` function of a module, if
+//! present, to pick the imports to provide, and refuse modules
+//! for which `check_version` fails.
+//!
+//! A `Trap` means the call must not return to the module.
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::abi;
+use crate::canon::CanonError;
+use crate::hash::HashConfig;
+use crate::id::{IdHash, IdVersion, HASH_BYTES};
+use crate::store::Store;
+
+/// The reason a host call cannot return to the module
+#[derive(Debug, Clone)]
+pub enum Trap {
+    /// The module passed an address outside of its linear memory
+    OutOfBounds,
+    /// A store operation failed, in an import without a status code
+    Store(CanonError),
+    /// The module can not run against the store, see `check_version`
+    Unsupported,
+}
+
+impl fmt::Display for Trap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Trap::OutOfBounds => write!(f, "memory access out of bounds"),
+            Trap::Store(e) => write!(f, "store error: {:?}", e),
+            Trap::Unsupported => write!(f, "unsupported module"),
+        }
+    }
+}
+
+impl std::error::Error for Trap {}
+
+/// The linear memory of a wasm module
+pub trait Memory {
+    /// Returns the size of the memory in bytes
+    fn size(&self) -> usize;
+
+    /// Reads `buf.len()` bytes starting at `offset`
+    fn read(&self, offset: usize, buf: &mut [u8]) -> Result<(), Trap>;
+
+    /// Writes `bytes` starting at `offset`
+    fn write(&mut self, offset: usize, bytes: &[u8]) -> Result<(), Trap>;
+}
+
+impl Memory for [u8] {
+    fn size(&self) -> usize {
+        self.len()
+    }
+
+    fn read(&self, offset: usize, buf: &mut [u8]) -> Result<(), Trap> {
+        let end = offset.checked_add(buf.len()).ok_or(Trap::OutOfBounds)?;
+        buf.copy_from_slice(self.get(offset..end).ok_or(Trap::OutOfBounds)?);
+        Ok(())
+    }
+
+    fn write(&mut self, offset: usize, bytes: &[u8]) -> Result<(), Trap> {
+        let end = offset.checked_add(bytes.len()).ok_or(Trap::OutOfBounds)?;
+        self.get_mut(offset..end)
+            .ok_or(Trap::OutOfBounds)?
+            .copy_from_slice(bytes);
+        Ok(())
+    }
+}
+
+// Pointers and lengths are passed as `i32`, but are unsigned
+fn addr(value: i32) -> usize {
+    value as u32 as usize
+}
+
+// Checks the bounds of a range before allocating a buffer for it
+fn check<M: Memory + ?Sized>(
+    memory: &M,
+    offset: i32,
+    len: usize,
+) -> Result<(), Trap> {
+    match addr(offset).checked_add(len) {
+        Some(end) if end <= memory.size() => Ok(()),
+        _ => Err(Trap::OutOfBounds),
+    }
+}
+
+fn read_vec<M: Memory + ?Sized>(
+    memory: &M,
+    offset: i32,
+    len: usize,
+) -> Result<Vec<u8>, Trap> {
+    check(memory, offset, len)?;
+    let mut buf = vec![0u8; len];
+    memory.read(addr(offset), &mut buf)?;
+    Ok(buf)
+}
+
+fn read_hash<M: Memory + ?Sized>(
+    memory: &M,
+    offset: usize,
+) -> Result<IdHash, Trap> {
+    let mut hash = IdHash::default();
+    memory.read(offset, &mut hash)?;
+    Ok(hash)
+}
+
+fn read_lens<M: Memory + ?Sized>(
+    memory: &M,
+    lens: i32,
+    count: i32,
+) -> Result<Vec<usize>, Trap> {
+    let bytes = read_vec(memory, lens, addr(count) * 4)?;
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|len| addr(i32::from_le_bytes([len[0], len[1], len[2], len[3]])))
+        .collect())
+}
+
+// The total length of a batch, which can not be addressed if it overflows
+fn total(lens: &[usize]) -> Result<usize, Trap> {
+    lens.iter()
+        .try_fold(0usize, |total, len| total.checked_add(*len))
+        .ok_or(Trap::OutOfBounds)
+}
+
+/// Checks that a module built against the given version of the ABI can run
+/// against the store
+///
+/// Modules of version 0 can not ask for the hash configuration of the host,
+/// and give their `Id`s the version of BLAKE2b without a domain, so they are
+/// refused under any other configuration, as are unknown versions.
+pub fn check_version(version: i32) -> Result<(), Trap> {
+    match version {
+        0 if Store::hash_config() == HashConfig::DEFAULT => Ok(()),
+        1..=abi::VERSION => Ok(()),
+        _ => Err(Trap::Unsupported),
+    }
+}
+
+/// The `put` import, returning the version of the `Id` of the bytes
+pub fn put<M: Memory + ?Sized>(
+    memory: &mut M,
+    buf: i32,
+    len: i32,
+    ret_hash: i32,
+) -> Result<i32, Trap> {
+    let bytes = read_vec(memory, buf, addr(len))?;
+    check(memory, ret_hash, HASH_BYTES)?;
+    let (hash, version) = Store::put_versioned(&bytes);
+    memory.write(addr(ret_hash), &hash)?;
+    Ok(version.byte() as i32)
+}
+
+/// The `put` import of version 0 of the ABI, returning nothing
+pub fn put_legacy<M: Memory + ?Sized>(
+    memory: &mut M,
+    buf: i32,
+    len: i32,
+    ret_hash: i32,
+) -> Result<(), Trap> {
+    put(memory, buf, len, ret_hash).map(|_| ())
+}
+
+/// The `get` import, returning a status code
+pub fn get<M: Memory + ?Sized>(
+    memory: &mut M,
+    hash: i32,
+    buf: i32,
+    len: i32,
+) -> Result<i32, Trap> {
+    let hash = read_hash(memory, addr(hash))?;
+    check(memory, buf, addr(len))?;
+    let mut bytes = vec![0u8; addr(len)];
+    match Store::get(&hash, &mut bytes) {
+        Ok(()) => {
+            memory.write(addr(buf), &bytes)?;
+            Ok(abi::OK)
+        }
+        Err(e) => Ok(abi::status(Err(e))),
+    }
+}
+
+/// The `get` import of version 0 of the ABI, trapping on failure
+pub fn get_legacy<M: Memory + ?Sized>(
+    memory: &mut M,
+    hash: i32,
+    buf: i32,
+    len: i32,
+) -> Result<(), Trap> {
+    match get(memory, hash, buf, len)? {
+        abi::OK => Ok(()),
+        status => Err(Trap::Store(abi::result(status).unwrap_err())),
+    }
+}
+
+/// The `hash` import, returning the version of the `Id` of the bytes
+pub fn hash<M: Memory + ?Sized>(
+    memory: &mut M,
+    buf: i32,
+    len: i32,
+    ret_hash: i32,
+) -> Result<i32, Trap> {
+    let bytes = read_vec(memory, buf, addr(len))?;
+    let (hash, version) = Store::hash_versioned(&bytes);
+    memory.write(addr(ret_hash), &hash)?;
+    Ok(version.byte() as i32)
+}
+
+/// The `hash` import of version 0 of the ABI, returning nothing
+pub fn hash_legacy<M: Memory + ?Sized>(
+    memory: &mut M,
+    buf: i32,
+    len: i32,
+    ret_hash: i32,
+) -> Result<(), Trap> {
+    hash(memory, buf, len, ret_hash).map(|_| ())
+}
+
+/// The `hash_version` import
+pub fn hash_version() -> i32 {
+    Store::id_version().byte() as i32
+}
+
+/// The `hash_with` import, returning a status code
+pub fn hash_with<M: Memory + ?Sized>(
+    memory: &mut M,
+    version: i32,
+    buf: i32,
+    len: i32,
+    ret_hash: i32,
+) -> Result<i32, Trap> {
+    let bytes = read_vec(memory, buf, addr(len))?;
+    let version = u8::try_from(version).ok().and_then(IdVersion::from_byte);
+    match version.and_then(|version| Store::hash_with(version, &bytes)) {
+        Some(hash) => {
+            memory.write(addr(ret_hash), &hash)?;
+            Ok(abi::OK)
+        }
+        None => Ok(abi::NOT_FOUND),
+    }
+}
+
+/// The `put_many` import, returning the version of the `Id`s of the values
+pub fn put_many<M: Memory + ?Sized>(
+    memory: &mut M,
+    bufs: i32,
+    lens: i32,
+    count: i32,
+    ret_hashes: i32,
+) -> Result<i32, Trap> {
+    let lens = read_lens(memory, lens, count)?;
+    let bytes = read_vec(memory, bufs, total(&lens)?)?;
+    let hashes_len = lens
+        .len()
+        .checked_mul(HASH_BYTES)
+        .ok_or(Trap::OutOfBounds)?;
+    check(memory, ret_hashes, hashes_len)?;
+
+    let mut blobs = Vec::with_capacity(lens.len());
+    let mut offset = 0;
+    for len in lens {
+        blobs.push(&bytes[offset..offset + len]);
+        offset += len;
+    }
+
+    // an empty batch is hashed for the current configuration
+    let versioned = Store::put_many_versioned(&blobs);
+    let version = versioned.first().map_or_else(Store::id_version, |v| v.1);
+    let hashes: Vec<IdHash> =
+        versioned.into_iter().map(|(hash, _)| hash).collect();
+    memory.write(addr(ret_hashes), &hashes.concat())?;
+    Ok(version.byte() as i32)
+}
+
+/// The `get_many` import, returning a status code
+pub fn get_many<M: Memory + ?Sized>(
+    memory: &mut M,
+    hashes: i32,
+    lens: i32,
+    count: i32,
+    bufs: i32,
+) -> Result<i32, Trap> {
+    let lens = read_lens(memory, lens, count)?;
+    let hashes: Vec<IdHash> = (0..lens.len())
+        .map(|i| read_hash(memory, addr(hashes) + i * HASH_BYTES))
+        .collect::<Result<_, _>>()?;
+
+    let total = total(&lens)?;
+    check(memory, bufs, total)?;
+    let mut bytes = vec![0u8; total];
+    let mut into = Vec::with_capacity(lens.len());
+    let mut rest = &mut bytes[..];
+    for len in lens {
+        let (head, tail) = rest.split_at_mut(len);
+        into.push(head);
+        rest = tail;
+    }
+
+    match Store::get_many(&hashes, &mut into) {
+        Ok(()) => {
+            memory.write(addr(bufs), &bytes)?;
+            Ok(abi::OK)
+        }
+        Err(e) => Ok(abi::status(Err(e))),
+    }
+}
@@ -18,11 +18,37 @@
 
 extern crate alloc;
 
+pub mod abi;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod archive;
 mod canon;
+mod external;
+mod fixed;
+mod hash;
 mod id;
 mod implementations;
+#[cfg(all(not(target_arch = "wasm32"), feature = "imports"))]
+pub mod imports;
+mod schema;
+#[cfg(feature = "serde")]
+pub mod serde;
 mod store;
+mod value;
+mod visit;
 
 pub use canon::{Canon, CanonError, EncodeToVec};
-pub use id::{Id, IdHash};
+pub use fixed::{FixedBE, FixedLE};
+pub use hash::{HashAlgorithm, HashConfig};
+pub use id::{Id, IdHash, IdVersion, TypedId};
+pub use schema::{CanonSchema, Endian, Fields, Schema, Variant};
+#[cfg(all(not(target_arch = "wasm32"), feature = "mock-bridge"))]
+pub use store::mock;
+#[cfg(all(not(target_arch = "wasm32"), feature = "async"))]
+pub use store::AsyncBackend;
+#[cfg(not(target_arch = "wasm32"))]
+pub use store::{
+    Backend, CacheBackend, CacheStats, MemoryBackend, StoreRef, StoreStats,
+};
 pub use store::{Sink, Source, Store};
+pub use value::{FieldValues, Value};
+pub use visit::{children_of, ChildrenOf, Order, VisitIds, Walk};
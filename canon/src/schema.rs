@@ -0,0 +1,626 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use core::cmp::{Ordering, Reverse};
+use core::fmt::{self, Write};
+use core::marker::PhantomData;
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroU128, NonZeroU16,
+    NonZeroU32, NonZeroU64, NonZeroU8, Wrapping,
+};
+use core::ops::{Range, RangeInclusive};
+use core::time::Duration;
+
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{CanonError, FixedBE, FixedLE, Id, TypedId};
+
+/// Byte order of a fixed-width integer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    /// Least significant byte first
+    Little,
+    /// Most significant byte first
+    Big,
+}
+
+/// Machine-readable description of how values of a type are encoded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schema {
+    /// Nothing is encoded
+    Unit,
+    /// A single byte, `0` for false and `1` for true
+    Bool,
+    /// A single byte
+    U8,
+    /// A LEB128 varint of an integer of `bits` bits.
+    ///
+    /// Signed integers are zigzag encoded first. 128 bit integers are
+    /// encoded as two 64 bit varints, the high bits first.
+    Varint {
+        /// Width of the integer
+        bits: u8,
+        /// Whether the integer is signed
+        signed: bool,
+    },
+    /// An integer encoded at its full width
+    Fixed {
+        /// Width of the integer
+        bits: u8,
+        /// Whether the integer is signed, in two's complement
+        signed: bool,
+        /// Byte order of the encoding
+        endian: Endian,
+    },
+    /// A `u64` varint length followed by that many bytes of UTF-8
+    String,
+    /// A tag byte, `0` for none or `1` followed by the value
+    Option(Box<Schema>),
+    /// A tag byte, `0` followed by the ok value or `1` followed by the error
+    Result(Box<Schema>, Box<Schema>),
+    /// A `u64` varint length followed by that many elements
+    Sequence(Box<Schema>),
+    /// A `u64` varint length followed by that many key-value pairs
+    Map(Box<Schema>, Box<Schema>),
+    /// A fixed number of elements, without a length prefix
+    Array(u64, Box<Schema>),
+    /// The elements one after another
+    Tuple(Vec<Schema>),
+    /// The fields one after another
+    Struct {
+        /// Name of the type
+        name: String,
+        /// Fields of the struct
+        fields: Fields,
+    },
+    /// A tag byte selecting the variant, followed by its fields
+    Enum {
+        /// Name of the type
+        name: String,
+        /// Variants of the enum
+        variants: Vec<Variant>,
+    },
+    /// An [`Id`], a version byte, a `u32` varint length and a 32 byte hash,
+    /// or the bytes of an inline `Id`
+    Id,
+}
+
+/// Fields of a struct or an enum variant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fields {
+    /// Fields with names
+    Named(Vec<(String, Schema)>),
+    /// Positional fields
+    Unnamed(Vec<Schema>),
+    /// No fields
+    Unit,
+}
+
+/// A variant of an enum
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variant {
+    /// Name of the variant
+    pub name: String,
+    /// Tag byte encoded before the fields
+    pub tag: u8,
+    /// Fields of the variant
+    pub fields: Fields,
+}
+
+/// Trait to describe the encoding of a `Canon` type
+pub trait CanonSchema {
+    /// Returns the schema of the type
+    fn schema() -> Schema;
+}
+
+impl Schema {
+    /// Returns the schema serialized as JSON
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        self.write_json(&mut json)
+            .expect("writing to a string never fails");
+        json
+    }
+
+    fn write_json(&self, w: &mut String) -> fmt::Result {
+        match self {
+            Schema::Unit => write!(w, r#"{{"type":"unit"}}"#),
+            Schema::Bool => write!(w, r#"{{"type":"bool"}}"#),
+            Schema::U8 => write!(w, r#"{{"type":"u8"}}"#),
+            Schema::Varint { bits, signed } => write!(
+                w,
+                r#"{{"type":"varint","bits":{},"signed":{}}}"#,
+                bits, signed
+            ),
+            Schema::Fixed {
+                bits,
+                signed,
+                endian,
+            } => {
+                let endian = match endian {
+                    Endian::Little => "little",
+                    Endian::Big => "big",
+                };
+                write!(
+                    w,
+                    r#"{{"type":"fixed","bits":{},"signed":{},"endian":"{}"}}"#,
+                    bits, signed, endian
+                )
+            }
+            Schema::String => write!(w, r#"{{"type":"string"}}"#),
+            Schema::Option(some) => {
+                write!(w, r#"{{"type":"option","some":"#)?;
+                some.write_json(w)?;
+                write!(w, "}}")
+            }
+            Schema::Result(ok, err) => {
+                write!(w, r#"{{"type":"result","ok":"#)?;
+                ok.write_json(w)?;
+                write!(w, r#","err":"#)?;
+                err.write_json(w)?;
+                write!(w, "}}")
+            }
+            Schema::Sequence(item) => {
+                write!(w, r#"{{"type":"sequence","item":"#)?;
+                item.write_json(w)?;
+                write!(w, "}}")
+            }
+            Schema::Map(key, value) => {
+                write!(w, r#"{{"type":"map","key":"#)?;
+                key.write_json(w)?;
+                write!(w, r#","value":"#)?;
+                value.write_json(w)?;
+                write!(w, "}}")
+            }
+            Schema::Array(len, item) => {
+                write!(w, r#"{{"type":"array","len":{},"item":"#, len)?;
+                item.write_json(w)?;
+                write!(w, "}}")
+            }
+            Schema::Tuple(items) => {
+                write!(w, r#"{{"type":"tuple","items":["#)?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        w.push(',');
+                    }
+                    item.write_json(w)?;
+                }
+                write!(w, "]}}")
+            }
+            Schema::Struct { name, fields } => {
+                write!(w, r#"{{"type":"struct","name":"#)?;
+                write_json_str(w, name)?;
+                w.push(',');
+                fields.write_json(w)?;
+                write!(w, "}}")
+            }
+            Schema::Enum { name, variants } => {
+                write!(w, r#"{{"type":"enum","name":"#)?;
+                write_json_str(w, name)?;
+                write!(w, r#","variants":["#)?;
+                for (i, variant) in variants.iter().enumerate() {
+                    if i > 0 {
+                        w.push(',');
+                    }
+                    write!(w, r#"{{"name":"#)?;
+                    write_json_str(w, &variant.name)?;
+                    write!(w, r#","tag":{},"#, variant.tag)?;
+                    variant.fields.write_json(w)?;
+                    write!(w, "}}")?;
+                }
+                write!(w, "]}}")
+            }
+            Schema::Id => write!(w, r#"{{"type":"id"}}"#),
+        }
+    }
+}
+
+impl Fields {
+    /// Returns the number of fields
+    pub fn len(&self) -> usize {
+        match self {
+            Fields::Named(fields) => fields.len(),
+            Fields::Unnamed(fields) => fields.len(),
+            Fields::Unit => 0,
+        }
+    }
+
+    /// Returns true if there are no fields
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Writes the `kind` and `fields` members of the enclosing JSON object
+    fn write_json(&self, w: &mut String) -> fmt::Result {
+        match self {
+            Fields::Named(fields) => {
+                write!(w, r#""kind":"named","fields":["#)?;
+                for (i, (name, schema)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        w.push(',');
+                    }
+                    write!(w, r#"{{"name":"#)?;
+                    write_json_str(w, name)?;
+                    write!(w, r#","schema":"#)?;
+                    schema.write_json(w)?;
+                    write!(w, "}}")?;
+                }
+                write!(w, "]")
+            }
+            Fields::Unnamed(fields) => {
+                write!(w, r#""kind":"unnamed","fields":["#)?;
+                for (i, schema) in fields.iter().enumerate() {
+                    if i > 0 {
+                        w.push(',');
+                    }
+                    write!(w, r#"{{"schema":"#)?;
+                    schema.write_json(w)?;
+                    write!(w, "}}")?;
+                }
+                write!(w, "]")
+            }
+            Fields::Unit => write!(w, r#""kind":"unit","fields":[]"#),
+        }
+    }
+}
+
+fn write_json_str(w: &mut String, s: &str) -> fmt::Result {
+    w.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => w.push_str("\\\""),
+            '\\' => w.push_str("\\\\"),
+            c if (c as u32) < 0x20 => write!(w, "\\u{:04x}", c as u32)?,
+            c => w.push(c),
+        }
+    }
+    w.push('"');
+    Ok(())
+}
+
+fn unit_variant(name: &str, tag: u8) -> Variant {
+    Variant {
+        name: name.into(),
+        tag,
+        fields: Fields::Unit,
+    }
+}
+
+impl CanonSchema for u8 {
+    fn schema() -> Schema {
+        Schema::U8
+    }
+}
+
+macro_rules! varint {
+    ($varint:ty, $signed:expr) => {
+        impl CanonSchema for $varint {
+            fn schema() -> Schema {
+                Schema::Varint {
+                    bits: <$varint>::BITS as u8,
+                    signed: $signed,
+                }
+            }
+        }
+    };
+}
+
+varint!(u16, false);
+varint!(i16, true);
+
+varint!(u32, false);
+varint!(i32, true);
+
+varint!(u64, false);
+varint!(i64, true);
+
+varint!(u128, false);
+varint!(i128, true);
+
+macro_rules! fixed {
+    ($int:ty, $signed:expr) => {
+        impl CanonSchema for FixedLE<$int> {
+            fn schema() -> Schema {
+                Schema::Fixed {
+                    bits: <$int>::BITS as u8,
+                    signed: $signed,
+                    endian: Endian::Little,
+                }
+            }
+        }
+
+        impl CanonSchema for FixedBE<$int> {
+            fn schema() -> Schema {
+                Schema::Fixed {
+                    bits: <$int>::BITS as u8,
+                    signed: $signed,
+                    endian: Endian::Big,
+                }
+            }
+        }
+    };
+}
+
+fixed!(u8, false);
+fixed!(i8, true);
+
+fixed!(u16, false);
+fixed!(i16, true);
+
+fixed!(u32, false);
+fixed!(i32, true);
+
+fixed!(u64, false);
+fixed!(i64, true);
+
+fixed!(u128, false);
+fixed!(i128, true);
+
+// The invariant of a non-zero integer is not part of its schema
+macro_rules! nonzero {
+    ($nonzero:ty, $int:ty) => {
+        impl CanonSchema for $nonzero {
+            fn schema() -> Schema {
+                <$int>::schema()
+            }
+        }
+    };
+}
+
+nonzero!(NonZeroU8, u8);
+
+nonzero!(NonZeroU16, u16);
+nonzero!(NonZeroI16, i16);
+
+nonzero!(NonZeroU32, u32);
+nonzero!(NonZeroI32, i32);
+
+nonzero!(NonZeroU64, u64);
+nonzero!(NonZeroI64, i64);
+
+nonzero!(NonZeroU128, u128);
+nonzero!(NonZeroI128, i128);
+
+impl CanonSchema for bool {
+    fn schema() -> Schema {
+        Schema::Bool
+    }
+}
+
+impl CanonSchema for () {
+    fn schema() -> Schema {
+        Schema::Unit
+    }
+}
+
+impl CanonSchema for ! {
+    fn schema() -> Schema {
+        Schema::Enum {
+            name: "!".into(),
+            variants: Vec::new(),
+        }
+    }
+}
+
+impl<T> CanonSchema for PhantomData<T> {
+    fn schema() -> Schema {
+        Schema::Unit
+    }
+}
+
+impl<T> CanonSchema for Option<T>
+where
+    T: CanonSchema,
+{
+    fn schema() -> Schema {
+        Schema::Option(Box::new(T::schema()))
+    }
+}
+
+impl<T, E> CanonSchema for Result<T, E>
+where
+    T: CanonSchema,
+    E: CanonSchema,
+{
+    fn schema() -> Schema {
+        Schema::Result(Box::new(T::schema()), Box::new(E::schema()))
+    }
+}
+
+impl<T> CanonSchema for Wrapping<T>
+where
+    T: CanonSchema,
+{
+    fn schema() -> Schema {
+        T::schema()
+    }
+}
+
+impl<T> CanonSchema for Reverse<T>
+where
+    T: CanonSchema,
+{
+    fn schema() -> Schema {
+        T::schema()
+    }
+}
+
+impl CanonSchema for Ordering {
+    fn schema() -> Schema {
+        Schema::Enum {
+            name: "Ordering".into(),
+            variants: vec![
+                unit_variant("Less", 0),
+                unit_variant("Equal", 1),
+                unit_variant("Greater", 2),
+            ],
+        }
+    }
+}
+
+impl CanonSchema for Duration {
+    fn schema() -> Schema {
+        Schema::Struct {
+            name: "Duration".into(),
+            fields: Fields::Named(vec![
+                ("secs".into(), u64::schema()),
+                ("nanos".into(), u32::schema()),
+            ]),
+        }
+    }
+}
+
+impl<T> CanonSchema for Range<T>
+where
+    T: CanonSchema,
+{
+    fn schema() -> Schema {
+        Schema::Struct {
+            name: "Range".into(),
+            fields: Fields::Named(vec![
+                ("start".into(), T::schema()),
+                ("end".into(), T::schema()),
+            ]),
+        }
+    }
+}
+
+impl<T> CanonSchema for RangeInclusive<T>
+where
+    T: CanonSchema,
+{
+    fn schema() -> Schema {
+        Schema::Struct {
+            name: "RangeInclusive".into(),
+            fields: Fields::Named(vec![
+                ("start".into(), T::schema()),
+                ("end".into(), T::schema()),
+            ]),
+        }
+    }
+}
+
+macro_rules! tuple {
+    ( $($name:ident)+) => (
+        impl<$($name,)+> CanonSchema for ($($name,)+)
+        where
+            $($name: CanonSchema,)+
+        {
+            fn schema() -> Schema {
+                Schema::Tuple(vec![$($name::schema(),)+])
+            }
+        }
+    );
+}
+
+tuple! { A }
+tuple! { A B }
+tuple! { A B C }
+tuple! { A B C D }
+tuple! { A B C D E }
+tuple! { A B C D E F }
+tuple! { A B C D E F G }
+tuple! { A B C D E F G H }
+tuple! { A B C D E F G H I }
+tuple! { A B C D E F G H I J }
+tuple! { A B C D E F G H I J K }
+tuple! { A B C D E F G H I J K L }
+tuple! { A B C D E F G H I J K L M }
+tuple! { A B C D E F G H I J K L M N }
+tuple! { A B C D E F G H I J K L M N O }
+tuple! { A B C D E F G H I J K L M N O P }
+
+impl<T, const N: usize> CanonSchema for [T; N]
+where
+    T: CanonSchema,
+{
+    fn schema() -> Schema {
+        Schema::Array(N as u64, Box::new(T::schema()))
+    }
+}
+
+impl<T> CanonSchema for Vec<T>
+where
+    T: CanonSchema,
+{
+    fn schema() -> Schema {
+        Schema::Sequence(Box::new(T::schema()))
+    }
+}
+
+impl<T> CanonSchema for BTreeSet<T>
+where
+    T: CanonSchema,
+{
+    fn schema() -> Schema {
+        Schema::Sequence(Box::new(T::schema()))
+    }
+}
+
+impl<K, V> CanonSchema for BTreeMap<K, V>
+where
+    K: CanonSchema,
+    V: CanonSchema,
+{
+    fn schema() -> Schema {
+        Schema::Map(Box::new(K::schema()), Box::new(V::schema()))
+    }
+}
+
+impl CanonSchema for String {
+    fn schema() -> Schema {
+        Schema::String
+    }
+}
+
+impl<T> CanonSchema for Rc<T>
+where
+    T: CanonSchema,
+{
+    fn schema() -> Schema {
+        T::schema()
+    }
+}
+
+impl<T> CanonSchema for Arc<T>
+where
+    T: CanonSchema,
+{
+    fn schema() -> Schema {
+        T::schema()
+    }
+}
+
+impl CanonSchema for Id {
+    fn schema() -> Schema {
+        Schema::Id
+    }
+}
+
+impl<T> CanonSchema for TypedId<T> {
+    fn schema() -> Schema {
+        Schema::Id
+    }
+}
+
+impl CanonSchema for CanonError {
+    fn schema() -> Schema {
+        Schema::Enum {
+            name: "CanonError".into(),
+            variants: vec![
+                unit_variant("InvalidEncoding", 0),
+                unit_variant("NotFound", 1),
+                unit_variant("Corrupted", 2),
+            ],
+        }
+    }
+}
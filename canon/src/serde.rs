@@ -0,0 +1,971 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Serde support, producing the same bytes as the `Canon` implementations.
+//!
+//! Integers are varints, except for `u8`, enum variants are tagged with a
+//! single byte and sequences, maps, strings and byte slices are prefixed
+//! with their length as a `u64` varint. Structs and tuples are encoded as
+//! their fields one after another.
+//!
+//! Types without a `Canon` counterpart, such as floats, `char` and `i8`,
+//! are rejected with [`Error::Unsupported`].
+
+use core::fmt;
+use core::iter;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use ::serde::de::{
+    self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use ::serde::ser::{
+    self, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant,
+    SerializeTuple, SerializeTupleStruct, SerializeTupleVariant,
+};
+use ::serde::{Deserialize, Serialize};
+
+use crate::id::{self, HASH_BYTES};
+use crate::{Canon, CanonError, Id, IdHash, Sink, Source, TypedId};
+
+/// The possible errors when serializing or deserializing through serde
+#[derive(Debug, Clone)]
+pub enum Error {
+    /// The bytes could not be decoded
+    Canon(CanonError),
+    /// The data model type has no `Canon` encoding
+    Unsupported(&'static str),
+    /// An error raised by a `Serialize` or `Deserialize` implementation
+    Custom(String),
+}
+
+impl From<CanonError> for Error {
+    fn from(e: CanonError) -> Self {
+        Error::Canon(e)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Canon(e) => write!(f, "canon error: {:?}", e),
+            Error::Unsupported(what) => write!(f, "unsupported: {}", what),
+            Error::Custom(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl ser::StdError for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Custom(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Custom(msg.to_string())
+    }
+}
+
+type Result<T> = core::result::Result<T, Error>;
+
+/// Serializes a value into the bytes of its `Canon` encoding
+pub fn to_vec<T>(value: &T) -> Result<Vec<u8>>
+where
+    T: Serialize + ?Sized,
+{
+    let mut serializer = Serializer::new();
+    value.serialize(&mut serializer)?;
+    Ok(serializer.into_inner())
+}
+
+/// Deserializes a value from the bytes of its `Canon` encoding
+///
+/// Trailing bytes are an error.
+pub fn from_slice<'de, T>(bytes: &'de [u8]) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    let mut deserializer = Deserializer::new(bytes);
+    let value = T::deserialize(&mut deserializer)?;
+    match deserializer.offset == bytes.len() {
+        true => Ok(value),
+        false => Err(CanonError::InvalidEncoding.into()),
+    }
+}
+
+/// Serde serializer writing the `Canon` wire format
+#[derive(Debug, Default)]
+pub struct Serializer {
+    output: Vec<u8>,
+}
+
+impl Serializer {
+    /// Creates a new serializer with an empty output
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the serialized bytes
+    pub fn into_inner(self) -> Vec<u8> {
+        self.output
+    }
+
+    fn put<T: Canon>(&mut self, t: &T) {
+        let start = self.output.len();
+        self.output.resize(start + t.encoded_len(), 0);
+        t.encode(&mut Sink::new(&mut self.output[start..]));
+    }
+
+    fn put_tag(&mut self, index: u32) -> Result<()> {
+        if index > u8::MAX as u32 {
+            return Err(Error::Unsupported("more than 256 enum variants"));
+        }
+        self.output.push(index as u8);
+        Ok(())
+    }
+
+    // Starts a length prefixed compound, deferring the prefix if unknown
+    fn prefixed(&mut self, len: Option<usize>) -> Compound<'_> {
+        let pending = match len {
+            Some(len) => {
+                self.put(&(len as u64));
+                None
+            }
+            None => Some(self.output.len()),
+        };
+        Compound {
+            ser: self,
+            pending,
+            count: 0,
+        }
+    }
+
+    fn unprefixed(&mut self) -> Compound<'_> {
+        Compound {
+            ser: self,
+            pending: None,
+            count: 0,
+        }
+    }
+}
+
+/// State of a sequence, map, tuple or struct being serialized
+#[derive(Debug)]
+pub struct Compound<'a> {
+    ser: &'a mut Serializer,
+    // Where to insert the length prefix, if it was not known up front
+    pending: Option<usize>,
+    count: u64,
+}
+
+impl<'a> Compound<'a> {
+    fn element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.count += 1;
+        value.serialize(&mut *self.ser)
+    }
+
+    fn finish(self) -> Result<()> {
+        if let Some(at) = self.pending {
+            let prefix = {
+                let mut ser = Serializer::new();
+                ser.put(&self.count);
+                ser.output
+            };
+            self.ser.output.splice(at..at, prefix);
+        }
+        Ok(())
+    }
+}
+
+impl<'a> ser::Serializer for &'a mut Serializer {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Compound<'a>;
+    type SerializeTuple = Compound<'a>;
+    type SerializeTupleStruct = Compound<'a>;
+    type SerializeTupleVariant = Compound<'a>;
+    type SerializeMap = Compound<'a>;
+    type SerializeStruct = Compound<'a>;
+    type SerializeStructVariant = Compound<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.put(&v);
+        Ok(())
+    }
+
+    fn serialize_i8(self, _: i8) -> Result<()> {
+        Err(Error::Unsupported("i8"))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.put(&v);
+        Ok(())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        self.put(&v);
+        Ok(())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        self.put(&v);
+        Ok(())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<()> {
+        self.put(&v);
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.put(&v);
+        Ok(())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.put(&v);
+        Ok(())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.put(&v);
+        Ok(())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        self.put(&v);
+        Ok(())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<()> {
+        self.put(&v);
+        Ok(())
+    }
+
+    fn serialize_f32(self, _: f32) -> Result<()> {
+        Err(Error::Unsupported("f32"))
+    }
+
+    fn serialize_f64(self, _: f64) -> Result<()> {
+        Err(Error::Unsupported("f64"))
+    }
+
+    fn serialize_char(self, _: char) -> Result<()> {
+        Err(Error::Unsupported("char"))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        self.serialize_bytes(v.as_bytes())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        self.put(&(v.len() as u64));
+        self.output.extend_from_slice(v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        self.output.push(0);
+        Ok(())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.output.push(1);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+    ) -> Result<()> {
+        self.put_tag(variant_index)
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        if name == ID_TOKEN {
+            // the padding of inline Ids is dropped, as in their encoding
+            let padded = to_vec(value)?;
+            let id = Id::decode(&mut Source::new(&padded))?;
+            self.put(&id);
+            return Ok(());
+        }
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+        value: &T,
+    ) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.put_tag(variant_index)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Compound<'a>> {
+        Ok(self.prefixed(len))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Compound<'a>> {
+        Ok(self.unprefixed())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Compound<'a>> {
+        Ok(self.unprefixed())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Compound<'a>> {
+        self.put_tag(variant_index)?;
+        Ok(self.unprefixed())
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Compound<'a>> {
+        Ok(self.prefixed(len))
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Compound<'a>> {
+        Ok(self.unprefixed())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Compound<'a>> {
+        self.put_tag(variant_index)?;
+        Ok(self.unprefixed())
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+impl<'a> SerializeSeq for Compound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a> SerializeTuple for Compound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a> SerializeTupleStruct for Compound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a> SerializeTupleVariant for Compound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a> SerializeMap for Compound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.element(key)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        // the pair was counted with its key
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a> SerializeStruct for Compound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, _: &'static str, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a> SerializeStructVariant for Compound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, _: &'static str, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+/// Serde deserializer reading the `Canon` wire format
+#[derive(Debug)]
+pub struct Deserializer<'de> {
+    bytes: &'de [u8],
+    offset: usize,
+}
+
+impl<'de> Deserializer<'de> {
+    /// Creates a new deserializer reading from `bytes`
+    pub fn new(bytes: &'de [u8]) -> Self {
+        Deserializer { bytes, offset: 0 }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'de [u8]> {
+        match self.bytes.len() - self.offset >= n {
+            true => {
+                let bytes = &self.bytes[self.offset..self.offset + n];
+                self.offset += n;
+                Ok(bytes)
+            }
+            false => Err(CanonError::InvalidEncoding.into()),
+        }
+    }
+
+    // Decodes a `Canon` value, making sure its `varints` are terminated
+    // within the remaining bytes first. The scan stops at the last of them,
+    // so a sequence of integers is decoded in linear time.
+    fn get<T: Canon>(&mut self, varints: usize) -> Result<T> {
+        const MSB: u8 = 0b1000_0000;
+        let rest = &self.bytes[self.offset..];
+        let ends = rest.iter().filter(|b| *b & MSB == 0).take(varints);
+        if ends.count() < varints {
+            return Err(CanonError::InvalidEncoding.into());
+        }
+        let mut source = Source::new(rest);
+        let t = T::decode(&mut source)?;
+        self.offset += source.offset;
+        Ok(t)
+    }
+
+    fn get_len(&mut self) -> Result<usize> {
+        let len: u64 = self.get(1)?;
+        match len as usize as u64 == len {
+            true => Ok(len as usize),
+            false => Err(CanonError::InvalidEncoding.into()),
+        }
+    }
+
+    // Decodes an Id, making sure the bytes cover its version, its length
+    // and the hash or inline bytes that follow
+    fn get_id(&mut self) -> Result<Id> {
+        let start = self.offset;
+        let version = self.take(1)?[0];
+        let len: u32 = self.get(1)?;
+        self.take(id::body_len(version, len)?)?;
+        let bytes = &self.bytes[start..self.offset];
+        Ok(Id::decode(&mut Source::new(bytes))?)
+    }
+
+    fn get_bytes(&mut self) -> Result<&'de [u8]> {
+        let len = self.get_len()?;
+        self.take(len)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value> {
+        Err(Error::Unsupported("self-describing deserialization"))
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.take(1)? {
+            [0] => visitor.visit_bool(false),
+            [1] => visitor.visit_bool(true),
+            _ => Err(CanonError::InvalidEncoding.into()),
+        }
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, _: V) -> Result<V::Value> {
+        Err(Error::Unsupported("i8"))
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_i16(self.get(1)?)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_i32(self.get(1)?)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_i64(self.get(1)?)
+    }
+
+    fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_i128(self.get(2)?)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_u8(self.take(1)?[0])
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_u16(self.get(1)?)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_u32(self.get(1)?)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_u64(self.get(1)?)
+    }
+
+    fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_u128(self.get(2)?)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, _: V) -> Result<V::Value> {
+        Err(Error::Unsupported("f32"))
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, _: V) -> Result<V::Value> {
+        Err(Error::Unsupported("f64"))
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, _: V) -> Result<V::Value> {
+        Err(Error::Unsupported("char"))
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let bytes = self.get_bytes()?;
+        let s = core::str::from_utf8(bytes)
+            .map_err(|_| Error::Canon(CanonError::InvalidEncoding))?;
+        visitor.visit_borrowed_str(s)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_borrowed_bytes(self.get_bytes()?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self.take(1)? {
+            [0] => visitor.visit_none(),
+            [1] => visitor.visit_some(self),
+            _ => Err(CanonError::InvalidEncoding.into()),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        if name == ID_TOKEN {
+            let id = self.get_id()?;
+            let fields: Vec<u32> = padded(&id).collect();
+            return visitor.visit_seq(de::value::SeqDeserializer::new(
+                fields.into_iter(),
+            ));
+        }
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let len = self.get_len()?;
+        visitor.visit_seq(Elements { de: self, len })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_seq(Elements { de: self, len })
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_seq(Elements { de: self, len })
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let len = self.get_len()?;
+        visitor.visit_map(Elements { de: self, len })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_seq(Elements {
+            de: self,
+            len: fields.len(),
+        })
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, _: V) -> Result<V::Value> {
+        Err(Error::Unsupported("identifiers"))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(
+        self,
+        _: V,
+    ) -> Result<V::Value> {
+        Err(Error::Unsupported("ignored values"))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+struct Elements<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    len: usize,
+}
+
+impl<'a, 'de> SeqAccess<'de> for Elements<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        // not trusting the length with a preallocation
+        Some(self.len.min(4096))
+    }
+}
+
+impl<'a, 'de> MapAccess<'de> for Elements<'a, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        self.next_element_seed(seed)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len.min(4096))
+    }
+}
+
+impl<'de> EnumAccess<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let tag = self.take(1)?[0] as u32;
+        let tag: de::value::U32Deserializer<Error> = tag.into_deserializer();
+        let variant = seed.deserialize(tag)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_seq(Elements { de: self, len })
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_seq(Elements {
+            de: self,
+            len: fields.len(),
+        })
+    }
+}
+
+// Ids are written as a tuple of their version, their length and the 32
+// bytes of their hash, or of the bytes of an inline Id padded with zeros.
+// The tuple has the same length for every Id, so any format can read it
+// back. The newtype name tells the serializer of this module to write the
+// `Canon` encoding of the Id instead, without the padding.
+const ID_TOKEN: &str = "$canonical::Id";
+
+// The elements of the tuple of an Id
+fn padded(id: &Id) -> impl Iterator<Item = u32> + '_ {
+    iter::once(id.version as u32)
+        .chain(iter::once(id.len))
+        .chain(id.hash.iter().map(|byte| *byte as u32))
+}
+
+impl Serialize for Id {
+    fn serialize<S: ser::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        struct Padded<'a>(&'a Id);
+
+        impl Serialize for Padded<'_> {
+            fn serialize<S: ser::Serializer>(
+                &self,
+                serializer: S,
+            ) -> core::result::Result<S::Ok, S::Error> {
+                let mut tuple = serializer.serialize_tuple(2 + HASH_BYTES)?;
+                tuple.serialize_element(&self.0.version)?;
+                tuple.serialize_element(&self.0.len)?;
+                for byte in &self.0.hash {
+                    tuple.serialize_element(byte)?;
+                }
+                tuple.end()
+            }
+        }
+
+        serializer.serialize_newtype_struct(ID_TOKEN, &Padded(self))
+    }
+}
+
+impl<'de> Deserialize<'de> for Id {
+    fn deserialize<D: de::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        struct IdVisitor;
+
+        impl<'de> Visitor<'de> for IdVisitor {
+            type Value = Id;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an id")
+            }
+
+            fn visit_newtype_struct<D: de::Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> core::result::Result<Id, D::Error> {
+                deserializer.deserialize_tuple(2 + HASH_BYTES, self)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> core::result::Result<Id, A::Error> {
+                let missing = || de::Error::custom("missing id field");
+                let version: u8 = seq.next_element()?.ok_or_else(missing)?;
+                let len: u32 = seq.next_element()?.ok_or_else(missing)?;
+
+                let mut hash = IdHash::default();
+                for byte in &mut hash {
+                    *byte = seq.next_element()?.ok_or_else(missing)?;
+                }
+
+                Id::from_parts(version, len, hash)
+                    .map_err(|_| de::Error::custom("invalid id"))
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(ID_TOKEN, IdVisitor)
+    }
+}
+
+impl<T> Serialize for TypedId<T> {
+    fn serialize<S: ser::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        self.id().serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for TypedId<T> {
+    fn deserialize<D: de::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        Id::deserialize(deserializer).map(TypedId::from_id)
+    }
+}
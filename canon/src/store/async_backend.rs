@@ -0,0 +1,72 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::future::Future;
+use std::sync::Arc;
+
+use super::backend::{Backend, MemoryBackend};
+use crate::canon::CanonError;
+use crate::id::IdHash;
+
+/// Storage of byte slices by their hash, accessed asynchronously
+///
+/// The asynchronous counterpart of `Backend`, for stores that would block the
+/// executor, such as stores accessed over the network or IPC. It is used with
+/// `Id::new_async` and `Id::reify_async`.
+pub trait AsyncBackend: Send + Sync {
+    /// Write the bytes of the entry with the given hash into a buffer
+    ///
+    /// Returns `CanonError::NotFound` if there is no such entry, and
+    /// `CanonError::Corrupted` if it does not have the length of the buffer.
+    fn get(
+        &self,
+        hash: &IdHash,
+        into: &mut [u8],
+    ) -> impl Future<Output = Result<(), CanonError>> + Send;
+
+    /// Store the bytes under the given hash
+    fn put(
+        &self,
+        hash: IdHash,
+        bytes: &[u8],
+    ) -> impl Future<Output = ()> + Send;
+}
+
+// Memory access never blocks, so the futures are ready when first polled
+impl AsyncBackend for MemoryBackend {
+    async fn get(
+        &self,
+        hash: &IdHash,
+        into: &mut [u8],
+    ) -> Result<(), CanonError> {
+        Backend::get(self, hash, into)
+    }
+
+    async fn put(&self, hash: IdHash, bytes: &[u8]) {
+        Backend::put(self, hash, bytes)
+    }
+}
+
+impl<B> AsyncBackend for Arc<B>
+where
+    B: AsyncBackend,
+{
+    fn get(
+        &self,
+        hash: &IdHash,
+        into: &mut [u8],
+    ) -> impl Future<Output = Result<(), CanonError>> + Send {
+        (**self).get(hash, into)
+    }
+
+    fn put(
+        &self,
+        hash: IdHash,
+        bytes: &[u8],
+    ) -> impl Future<Output = ()> + Send {
+        (**self).put(hash, bytes)
+    }
+}
@@ -0,0 +1,538 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+use super::{host, Inner};
+use crate::canon::CanonError;
+use crate::id::{Id, IdHash, IdVersion};
+
+/// Storage of byte slices by their hash, backing a `StoreRef`
+///
+/// Hashing is done by the `Store`, using its hash configuration, so backends
+/// only keep the bytes under the hash they are given.
+pub trait Backend: Send + Sync {
+    /// Write the bytes of the entry with the given hash into a buffer
+    ///
+    /// Returns `CanonError::NotFound` if there is no such entry, and
+    /// `CanonError::Corrupted` if it does not have the length of the buffer.
+    fn get(&self, hash: &IdHash, into: &mut [u8]) -> Result<(), CanonError>;
+
+    /// Store the bytes under the given hash
+    fn put(&self, hash: IdHash, bytes: &[u8]);
+
+    /// Remove the entry with the given hash, returning its bytes
+    fn remove(&self, hash: &IdHash) -> Option<Vec<u8>>;
+
+    /// Write the bytes of several entries, returning the first error
+    fn get_many(
+        &self,
+        hashes: &[IdHash],
+        into: &mut [&mut [u8]],
+    ) -> Result<(), CanonError> {
+        for (hash, into) in hashes.iter().zip(into.iter_mut()) {
+            self.get(hash, into)?;
+        }
+        Ok(())
+    }
+
+    /// Store several entries
+    fn put_many(&self, entries: &[(IdHash, &[u8])]) {
+        for (hash, bytes) in entries {
+            self.put(*hash, bytes);
+        }
+    }
+
+    /// Returns the hash and size of each entry
+    ///
+    /// Backends unable to list their entries return none.
+    fn entries(&self) -> Vec<(IdHash, usize)> {
+        Vec::new()
+    }
+
+    /// Returns statistics on the entries and the use of the backend
+    ///
+    /// By default, the entries are counted from `entries`, and no gets are
+    /// counted.
+    fn stats(&self) -> StoreStats {
+        StoreStats::of_entries(self.entries())
+    }
+
+    /// Returns the hash and size of the `n` largest entries, largest first
+    ///
+    /// By default, the entries are sorted from `entries`.
+    fn largest(&self, n: usize) -> Vec<(IdHash, usize)> {
+        let mut entries = self.entries();
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then(b.0.cmp(&a.0)));
+        entries.truncate(n);
+        entries
+    }
+}
+
+// Allows keeping a handle to a backend moved into a `StoreRef`
+impl<B> Backend for Arc<B>
+where
+    B: Backend + ?Sized,
+{
+    fn get(&self, hash: &IdHash, into: &mut [u8]) -> Result<(), CanonError> {
+        (**self).get(hash, into)
+    }
+
+    fn put(&self, hash: IdHash, bytes: &[u8]) {
+        (**self).put(hash, bytes)
+    }
+
+    fn remove(&self, hash: &IdHash) -> Option<Vec<u8>> {
+        (**self).remove(hash)
+    }
+
+    fn get_many(
+        &self,
+        hashes: &[IdHash],
+        into: &mut [&mut [u8]],
+    ) -> Result<(), CanonError> {
+        (**self).get_many(hashes, into)
+    }
+
+    fn put_many(&self, entries: &[(IdHash, &[u8])]) {
+        (**self).put_many(entries)
+    }
+
+    fn entries(&self) -> Vec<(IdHash, usize)> {
+        (**self).entries()
+    }
+
+    fn stats(&self) -> StoreStats {
+        (**self).stats()
+    }
+
+    fn largest(&self, n: usize) -> Vec<(IdHash, usize)> {
+        (**self).largest(n)
+    }
+}
+
+/// Statistics on the entries of a store and its use
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StoreStats {
+    /// The number of entries
+    pub entries: usize,
+    /// The total size of the entries in bytes
+    pub bytes: usize,
+    /// The size of the largest entry in bytes
+    pub largest: usize,
+    /// The number of gets reading their entry
+    pub hits: u64,
+    /// The number of gets not finding their entry, or finding it with
+    /// another length
+    pub misses: u64,
+}
+
+impl StoreStats {
+    fn of_entries<I>(entries: I) -> Self
+    where
+        I: IntoIterator<Item = (IdHash, usize)>,
+    {
+        entries
+            .into_iter()
+            .fold(StoreStats::default(), |stats, (_, size)| StoreStats {
+                entries: stats.entries + 1,
+                bytes: stats.bytes + size,
+                largest: stats.largest.max(size),
+                ..stats
+            })
+    }
+}
+
+/// A backend keeping its entries in memory
+///
+/// The number, total size and sizes of the entries are kept up to date as
+/// they are put and removed, so `stats` and `largest` do not go through all
+/// entries.
+#[derive(Default)]
+pub struct MemoryBackend {
+    entries: RwLock<Entries>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Default)]
+struct Entries {
+    map: BTreeMap<IdHash, Vec<u8>>,
+    sizes: BTreeSet<(usize, IdHash)>,
+    bytes: usize,
+}
+
+impl Entries {
+    fn get(&self, hash: &IdHash) -> Option<&Vec<u8>> {
+        self.map.get(hash)
+    }
+
+    fn insert(&mut self, hash: IdHash, bytes: &[u8]) {
+        if let Some(old) = self.map.insert(hash, Vec::from(bytes)) {
+            self.sizes.remove(&(old.len(), hash));
+            self.bytes -= old.len();
+        }
+        self.sizes.insert((bytes.len(), hash));
+        self.bytes += bytes.len();
+    }
+
+    fn remove(&mut self, hash: &IdHash) -> Option<Vec<u8>> {
+        let bytes = self.map.remove(hash)?;
+        self.sizes.remove(&(bytes.len(), *hash));
+        self.bytes -= bytes.len();
+        Some(bytes)
+    }
+}
+
+impl MemoryBackend {
+    /// Creates a new, empty, backend
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of entries
+    pub fn len(&self) -> usize {
+        self.entries.read().map.len()
+    }
+
+    /// Returns `true` if there are no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.read().map.is_empty()
+    }
+
+    // Counts a get as a hit, or as a miss if it did not find its entry or
+    // found it with another length
+    fn count<T>(&self, result: Result<T, CanonError>) -> Result<T, CanonError> {
+        match result {
+            Ok(_) => &self.hits,
+            Err(_) => &self.misses,
+        }
+        .fetch_add(1, Ordering::Relaxed);
+        result
+    }
+}
+
+impl fmt::Debug for MemoryBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MemoryBackend({} entries)", self.len())
+    }
+}
+
+fn copy_entry(
+    entry: Option<&Vec<u8>>,
+    into: &mut [u8],
+) -> Result<(), CanonError> {
+    match entry {
+        Some(vec) if vec.len() == into.len() => {
+            into.copy_from_slice(vec);
+            Ok(())
+        }
+        Some(_) => Err(CanonError::Corrupted),
+        None => Err(CanonError::NotFound),
+    }
+}
+
+impl Backend for MemoryBackend {
+    fn get(&self, hash: &IdHash, into: &mut [u8]) -> Result<(), CanonError> {
+        self.count(copy_entry(self.entries.read().get(hash), into))
+    }
+
+    fn put(&self, hash: IdHash, bytes: &[u8]) {
+        self.entries.write().insert(hash, bytes);
+    }
+
+    fn remove(&self, hash: &IdHash) -> Option<Vec<u8>> {
+        self.entries.write().remove(hash)
+    }
+
+    fn get_many(
+        &self,
+        hashes: &[IdHash],
+        into: &mut [&mut [u8]],
+    ) -> Result<(), CanonError> {
+        let map = self.entries.read();
+        for (hash, into) in hashes.iter().zip(into.iter_mut()) {
+            self.count(copy_entry(map.get(hash), into))?;
+        }
+        Ok(())
+    }
+
+    fn put_many(&self, entries: &[(IdHash, &[u8])]) {
+        let mut map = self.entries.write();
+        for (hash, bytes) in entries {
+            map.insert(*hash, bytes);
+        }
+    }
+
+    fn entries(&self) -> Vec<(IdHash, usize)> {
+        let entries = self.entries.read();
+        entries
+            .map
+            .iter()
+            .map(|(hash, bytes)| (*hash, bytes.len()))
+            .collect()
+    }
+
+    fn stats(&self) -> StoreStats {
+        let entries = self.entries.read();
+        StoreStats {
+            entries: entries.map.len(),
+            bytes: entries.bytes,
+            largest: entries.sizes.iter().next_back().map_or(0, |e| e.0),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    fn largest(&self, n: usize) -> Vec<(IdHash, usize)> {
+        let entries = self.entries.read();
+        entries
+            .sizes
+            .iter()
+            .rev()
+            .take(n)
+            .map(|(size, hash)| (*hash, *size))
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+enum Target {
+    Global,
+    Backend(Arc<dyn Backend>),
+}
+
+/// A handle to a store, either the global one or a backend
+///
+/// Handles are cheap to clone, and clones refer to the same store. All stores
+/// hash with the hash configuration of the process, set with
+/// `Store::set_hash_config`, so tenants sharing a process share it too.
+#[derive(Clone)]
+pub struct StoreRef {
+    target: Target,
+}
+
+impl fmt::Debug for StoreRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.target {
+            Target::Global => write!(f, "StoreRef(global)"),
+            Target::Backend(_) => write!(f, "StoreRef(backend)"),
+        }
+    }
+}
+
+impl<B> From<B> for StoreRef
+where
+    B: Backend + 'static,
+{
+    fn from(backend: B) -> Self {
+        StoreRef::new(backend)
+    }
+}
+
+impl StoreRef {
+    /// Creates a handle to a store with the given backend
+    pub fn new<B>(backend: B) -> Self
+    where
+        B: Backend + 'static,
+    {
+        StoreRef {
+            target: Target::Backend(Arc::new(backend)),
+        }
+    }
+
+    /// Creates a handle to a new, empty, store kept in memory
+    pub fn memory() -> Self {
+        Self::new(MemoryBackend::new())
+    }
+
+    /// Returns a handle to the global store
+    pub const fn global() -> Self {
+        StoreRef {
+            target: Target::Global,
+        }
+    }
+
+    /// Returns a handle to the store used by `Store` on the current thread,
+    /// set by `Store::scoped`, or the global store
+    pub fn current() -> Self {
+        scoped().unwrap_or_else(Self::global)
+    }
+
+    /// Returns `true` if both handles refer to the same store
+    pub fn same(&self, other: &StoreRef) -> bool {
+        match (&self.target, &other.target) {
+            (Target::Global, Target::Global) => true,
+            (Target::Backend(a), Target::Backend(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+
+    /// Write the byte slice into the store and return its hash
+    pub fn put(&self, bytes: &[u8]) -> IdHash {
+        self.put_versioned(bytes).0
+    }
+
+    pub(crate) fn put_versioned(&self, bytes: &[u8]) -> (IdHash, IdVersion) {
+        match &self.target {
+            Target::Global => Inner::put_versioned(bytes),
+            Target::Backend(backend) => {
+                let (hash, version) = Inner::hash_versioned(bytes);
+                backend.put(hash, bytes);
+                (hash, version)
+            }
+        }
+    }
+
+    /// Get data with the corresponding hash and write it to a buffer
+    pub fn get(
+        &self,
+        hash: &IdHash,
+        into: &mut [u8],
+    ) -> Result<(), CanonError> {
+        match &self.target {
+            Target::Global => Inner::get(hash, into),
+            Target::Backend(backend) => backend.get(hash, into),
+        }
+    }
+
+    /// Write several byte slices into the store, returning their hashes
+    pub fn put_many(&self, blobs: &[&[u8]]) -> Vec<IdHash> {
+        let versioned = self.put_many_versioned(blobs);
+        versioned.into_iter().map(|(hash, _)| hash).collect()
+    }
+
+    pub(crate) fn put_many_versioned(
+        &self,
+        blobs: &[&[u8]],
+    ) -> Vec<(IdHash, IdVersion)> {
+        match &self.target {
+            Target::Global => Inner::put_many_versioned(blobs),
+            Target::Backend(backend) => {
+                let versioned: Vec<_> =
+                    blobs.iter().map(|b| Inner::hash_versioned(b)).collect();
+                let entries: Vec<_> = versioned
+                    .iter()
+                    .zip(blobs)
+                    .map(|((hash, _), bytes)| (*hash, *bytes))
+                    .collect();
+                backend.put_many(&entries);
+                versioned
+            }
+        }
+    }
+
+    /// Get the data of several hashes, writing each to the buffer at the same
+    /// position in `into`
+    pub fn get_many(
+        &self,
+        hashes: &[IdHash],
+        into: &mut [&mut [u8]],
+    ) -> Result<(), CanonError> {
+        match &self.target {
+            Target::Global => Inner::get_many(hashes, into),
+            Target::Backend(backend) => backend.get_many(hashes, into),
+        }
+    }
+
+    /// Returns statistics on the entries of the store and its use
+    pub fn stats(&self) -> StoreStats {
+        match &self.target {
+            Target::Global => host::HostStore::stats(),
+            Target::Backend(backend) => backend.stats(),
+        }
+    }
+
+    /// Returns an iterator over the hash and size of each entry in the store,
+    /// in no particular order
+    ///
+    /// The iterator is a snapshot of the entries when called.
+    pub fn entries(&self) -> impl Iterator<Item = (IdHash, usize)> {
+        match &self.target {
+            Target::Global => host::HostStore::entries(),
+            Target::Backend(backend) => backend.entries(),
+        }
+        .into_iter()
+    }
+
+    /// Returns the hash and size of the `n` largest entries in the store,
+    /// largest first
+    pub fn largest(&self, n: usize) -> Vec<(IdHash, usize)> {
+        match &self.target {
+            Target::Global => host::HostStore::largest(n),
+            Target::Backend(backend) => backend.largest(n),
+        }
+    }
+
+    // Stores the bytes of a hashed Id under its hash, after checking them
+    // against the hash configuration recorded by the Id
+    pub(crate) fn insert(
+        &self,
+        id: &Id,
+        bytes: &[u8],
+    ) -> Result<(), CanonError> {
+        id.verify(bytes)?;
+        match &self.target {
+            Target::Global => host::HostStore::insert(id.hash, bytes),
+            Target::Backend(backend) => backend.put(id.hash, bytes),
+        }
+        Ok(())
+    }
+
+    pub(crate) fn take_bytes(&self, id: &Id) -> Result<Vec<u8>, CanonError> {
+        match &self.target {
+            Target::Global => Inner::take_bytes(id),
+            Target::Backend(backend) => take_entry(backend, id),
+        }
+    }
+}
+
+// Removes the entry of an Id from a backend, putting it back if it does not
+// have the length of the Id, so that a corrupted take loses no bytes
+pub(crate) fn take_entry<B: Backend + ?Sized>(
+    backend: &B,
+    id: &Id,
+) -> Result<Vec<u8>, CanonError> {
+    match backend.remove(&id.hash) {
+        Some(vec) if id.size() == vec.len() => Ok(vec),
+        Some(vec) => {
+            backend.put(id.hash, &vec);
+            Err(CanonError::Corrupted)
+        }
+        None => Err(CanonError::NotFound),
+    }
+}
+
+thread_local! {
+    static SCOPED: RefCell<Option<StoreRef>> = const { RefCell::new(None) };
+}
+
+// Restores the previously scoped store, also when unwinding
+struct ScopeGuard(Option<StoreRef>);
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        SCOPED.with(|scoped| *scoped.borrow_mut() = previous);
+    }
+}
+
+// Returns the scoped store of the current thread, if any
+pub(crate) fn scoped() -> Option<StoreRef> {
+    SCOPED.with(|scoped| scoped.borrow().clone())
+}
+
+pub(crate) fn scope<R>(store: StoreRef, f: impl FnOnce() -> R) -> R {
+    let previous = SCOPED.with(|scoped| scoped.borrow_mut().replace(store));
+    let _guard = ScopeGuard(previous);
+    f()
+}
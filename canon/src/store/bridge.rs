@@ -6,8 +6,13 @@
 
 extern crate alloc;
 
+use cfg_if::cfg_if;
+
+#[cfg(not(feature = "legacy-abi"))]
+use crate::abi;
 use crate::canon::CanonError;
-use crate::id::{Id, IdHash};
+use crate::id::{Id, IdHash, IdVersion};
+use alloc::vec;
 use alloc::vec::Vec;
 
 /// Store usable across ffi-boundaries
@@ -16,43 +21,234 @@ pub struct BridgeStore;
 
 impl BridgeStore {
     pub(crate) fn put(bytes: &[u8]) -> IdHash {
-        // We only put larger values here
-        debug_assert!(bytes.len() > core::mem::size_of::<IdHash>());
+        Self::put_versioned(bytes).0
+    }
+
+    pub(crate) fn put_versioned(bytes: &[u8]) -> (IdHash, IdVersion) {
         let mut idhash = IdHash::default();
-        unsafe {
-            put(&bytes[0], bytes.len() as i32, &mut idhash);
+        let len = bytes.len() as i32;
+        cfg_if! {
+            if #[cfg(feature = "legacy-abi")] {
+                unsafe { put(bytes.as_ptr(), len, &mut idhash) };
+                (idhash, Self::id_version())
+            } else {
+                let version = unsafe { put(bytes.as_ptr(), len, &mut idhash) };
+                (idhash, Self::version_of(version))
+            }
         }
-        idhash
     }
 
     pub fn get(hash: &IdHash, into: &mut [u8]) -> Result<(), CanonError> {
-        // We assume this to always work for the bridge, by catching the error
-        // in the host and aborting before returning.
         let len = into.len();
-        unsafe { Ok(get(&hash, &mut into[0], len as i32)) }
+        cfg_if! {
+            if #[cfg(feature = "legacy-abi")] {
+                // We assume this to always work for the bridge, by catching
+                // the error in the host and aborting before returning.
+                unsafe { get(hash, into.as_mut_ptr(), len as i32) };
+                Ok(())
+            } else {
+                abi::result(unsafe { get(hash, into.as_mut_ptr(), len as i32) })
+            }
+        }
+    }
+
+    pub(crate) fn put_many(blobs: &[&[u8]]) -> Vec<IdHash> {
+        let versioned = Self::put_many_versioned(blobs);
+        versioned.into_iter().map(|(hash, _)| hash).collect()
+    }
+
+    #[cfg(not(feature = "legacy-abi"))]
+    pub(crate) fn put_many_versioned(
+        blobs: &[&[u8]],
+    ) -> Vec<(IdHash, IdVersion)> {
+        let lens: Vec<i32> = blobs.iter().map(|b| b.len() as i32).collect();
+        let bytes = blobs.concat();
+        let mut hashes = vec![IdHash::default(); blobs.len()];
+        let version = unsafe {
+            put_many(
+                bytes.as_ptr(),
+                lens.as_ptr(),
+                blobs.len() as i32,
+                hashes.as_mut_ptr(),
+            )
+        };
+        let version = Self::version_of(version);
+        hashes.into_iter().map(|hash| (hash, version)).collect()
+    }
+
+    #[cfg(not(feature = "legacy-abi"))]
+    pub(crate) fn get_many(
+        hashes: &[IdHash],
+        into: &mut [&mut [u8]],
+    ) -> Result<(), CanonError> {
+        let lens: Vec<i32> = into.iter().map(|b| b.len() as i32).collect();
+        let total = into.iter().map(|b| b.len()).sum();
+        let mut buf = vec![0u8; total];
+        abi::result(unsafe {
+            get_many(
+                hashes.as_ptr(),
+                lens.as_ptr(),
+                hashes.len() as i32,
+                buf.as_mut_ptr(),
+            )
+        })?;
+
+        let mut offset = 0;
+        for into in into.iter_mut() {
+            let len = into.len();
+            into.copy_from_slice(&buf[offset..offset + len]);
+            offset += len;
+        }
+        Ok(())
+    }
+
+    // Version 0 of the ABI has no batched imports
+    #[cfg(feature = "legacy-abi")]
+    pub(crate) fn put_many_versioned(
+        blobs: &[&[u8]],
+    ) -> Vec<(IdHash, IdVersion)> {
+        blobs
+            .iter()
+            .map(|bytes| Self::put_versioned(bytes))
+            .collect()
+    }
+
+    #[cfg(feature = "legacy-abi")]
+    pub(crate) fn get_many(
+        hashes: &[IdHash],
+        into: &mut [&mut [u8]],
+    ) -> Result<(), CanonError> {
+        for (hash, into) in hashes.iter().zip(into.iter_mut()) {
+            Self::get(hash, into)?;
+        }
+        Ok(())
     }
 
     pub fn hash(bytes: &[u8]) -> IdHash {
-        let len = bytes.len();
-        let ofs = &bytes[0];
+        Self::hash_versioned(bytes).0
+    }
+
+    pub(crate) fn hash_versioned(bytes: &[u8]) -> (IdHash, IdVersion) {
         let mut result = IdHash::default();
-        unsafe { hash(ofs, len as i32, &mut result) };
-        result
+        let len = bytes.len() as i32;
+        cfg_if! {
+            if #[cfg(feature = "legacy-abi")] {
+                unsafe { hash(bytes.as_ptr(), len, &mut result) };
+                (result, Self::id_version())
+            } else {
+                let version = unsafe { hash(bytes.as_ptr(), len, &mut result) };
+                (result, Self::version_of(version))
+            }
+        }
+    }
+
+    // Hosts of version 0 of the ABI hash with BLAKE2b without a domain
+    #[cfg(feature = "legacy-abi")]
+    pub(crate) fn id_version() -> IdVersion {
+        IdVersion::Blake2b
+    }
+
+    #[cfg(feature = "legacy-abi")]
+    pub(crate) fn hash_with(
+        version: IdVersion,
+        bytes: &[u8],
+    ) -> Option<IdHash> {
+        match version == IdVersion::Blake2b {
+            true => Some(Self::hash(bytes)),
+            false => None,
+        }
+    }
+
+    #[cfg(not(feature = "legacy-abi"))]
+    pub(crate) fn id_version() -> IdVersion {
+        Self::version_of(unsafe { hash_version() })
+    }
+
+    // The hashing imports return the version of the Ids the host hashed for
+    // from version 1 of the ABI, so that it can not change between the calls
+    #[cfg(not(feature = "legacy-abi"))]
+    fn version_of(version: i32) -> IdVersion {
+        let byte = version as u8;
+        match IdVersion::from_byte(byte) {
+            Some(version) if version != IdVersion::Inline => version,
+            _ => panic!("the host hashes with an invalid id version {}", byte),
+        }
+    }
+
+    #[cfg(not(feature = "legacy-abi"))]
+    pub(crate) fn hash_with(
+        version: IdVersion,
+        bytes: &[u8],
+    ) -> Option<IdHash> {
+        let mut result = IdHash::default();
+        let status = unsafe {
+            hash_with(
+                version.byte() as i32,
+                bytes.as_ptr(),
+                bytes.len() as i32,
+                &mut result,
+            )
+        };
+        abi::result(status).ok().map(|_| result)
     }
 
     pub fn take_bytes(id: &Id) -> Result<Vec<u8>, CanonError> {
         // No-op in bridge version
-        let len = id.size();
-        let mut buf = Vec::with_capacity(len);
-        buf.resize_with(len, || 0);
+        let mut buf = vec![0u8; id.size()];
         Self::get(&id.hash(), &mut buf[..])?;
         Ok(buf)
     }
 }
 
+// Outside of wasm, the imports are provided by an in-process mock host
+#[cfg(not(target_arch = "wasm32"))]
+use super::mock::*;
+
+// The ABI is documented in the `abi` module. Pointers may dangle when
+// passed along with a zero length.
+//
+// The batched imports pass `count` values as their lengths in an array of
+// `i32`, and their bytes concatenated in a single buffer. Hashes are passed
+// as an array of `count` 32 byte hashes.
+#[cfg(all(target_arch = "wasm32", not(feature = "legacy-abi")))]
+#[link(wasm_import_module = "canon")]
+extern "C" {
+    pub fn put(buf: *const u8, len: i32, ret_hash: &mut IdHash) -> i32;
+    pub fn get(hash: &IdHash, buf: *mut u8, len: i32) -> i32;
+    pub fn hash(buf: *const u8, len: i32, ret_hash: &mut IdHash) -> i32;
+    pub fn hash_version() -> i32;
+    pub fn hash_with(
+        version: i32,
+        buf: *const u8,
+        len: i32,
+        ret_hash: &mut IdHash,
+    ) -> i32;
+    pub fn put_many(
+        bufs: *const u8,
+        lens: *const i32,
+        count: i32,
+        ret_hashes: *mut IdHash,
+    ) -> i32;
+    pub fn get_many(
+        hashes: *const IdHash,
+        lens: *const i32,
+        count: i32,
+        bufs: *mut u8,
+    ) -> i32;
+}
+
+// The imports of version 0 of the ABI return nothing
+#[cfg(all(target_arch = "wasm32", feature = "legacy-abi"))]
 #[link(wasm_import_module = "canon")]
 extern "C" {
-    pub fn put(buf: &u8, len: i32, ret_hash: &mut IdHash);
-    pub fn get(hash: &IdHash, buf: &mut u8, len: i32);
-    pub fn hash(ofs: &u8, len: i32, buf: &mut IdHash);
+    pub fn put(buf: *const u8, len: i32, ret_hash: &mut IdHash);
+    pub fn get(hash: &IdHash, buf: *mut u8, len: i32);
+    pub fn hash(buf: *const u8, len: i32, ret_hash: &mut IdHash);
+}
+
+/// Reports the version of the `canon` import module ABI to the host
+#[cfg(all(target_arch = "wasm32", not(feature = "legacy-abi")))]
+#[no_mangle]
+pub extern "C" fn canon_abi_version() -> i32 {
+    abi::VERSION
 }
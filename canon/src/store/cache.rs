@@ -0,0 +1,219 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use lru::LruCache;
+use parking_lot::Mutex;
+
+use super::backend::{Backend, StoreStats};
+use crate::canon::CanonError;
+use crate::id::IdHash;
+
+/// A backend keeping the most recently used entries of another backend in
+/// memory, up to a capacity in bytes
+///
+/// Entries are written through to the inner backend, and cached. Entries
+/// larger than the capacity are never cached.
+///
+/// Entries read or written concurrently with a `remove` are not cached, so
+/// that no entry is left cached once it is removed from the inner backend.
+pub struct CacheBackend<B> {
+    inner: B,
+    capacity: usize,
+    cache: Mutex<Cache>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+struct Cache {
+    lru: LruCache<IdHash, Vec<u8>>,
+    bytes: usize,
+    // bumped by every remove, once the entry is gone from the inner backend
+    generation: u64,
+}
+
+/// Statistics on the use of the cache of a `CacheBackend`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// The number of cached entries
+    pub entries: usize,
+    /// The total size of the cached entries in bytes
+    pub bytes: usize,
+    /// The number of gets served from the cache
+    pub hits: u64,
+    /// The number of gets passed to the inner backend
+    pub misses: u64,
+}
+
+impl CacheStats {
+    /// Returns the fraction of gets served from the cache, or zero if there
+    /// were none
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            gets => self.hits as f64 / gets as f64,
+        }
+    }
+}
+
+impl<B> CacheBackend<B> {
+    /// Creates a backend caching up to `capacity` bytes of `inner`
+    pub fn new(inner: B, capacity: usize) -> Self {
+        CacheBackend {
+            inner,
+            capacity,
+            cache: Mutex::new(Cache {
+                lru: LruCache::unbounded(),
+                bytes: 0,
+                generation: 0,
+            }),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the inner backend
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    /// Returns the capacity of the cache in bytes
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns statistics on the use of the cache
+    pub fn cache_stats(&self) -> CacheStats {
+        let cache = self.cache.lock();
+        CacheStats {
+            entries: cache.lru.len(),
+            bytes: cache.bytes,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Empties the cache, keeping the statistics
+    pub fn clear(&self) {
+        let mut cache = self.cache.lock();
+        cache.lru.clear();
+        cache.bytes = 0;
+    }
+
+    fn generation(&self) -> u64 {
+        self.cache.lock().generation
+    }
+
+    // Caches an entry read or written by the inner backend, unless a remove
+    // happened since `generation` was read
+    fn cache(&self, generation: u64, hash: IdHash, bytes: &[u8]) {
+        if bytes.len() > self.capacity {
+            return;
+        }
+
+        let mut cache = self.cache.lock();
+        if cache.generation != generation {
+            return;
+        }
+        if let Some(old) = cache.lru.put(hash, Vec::from(bytes)) {
+            cache.bytes -= old.len();
+        }
+        cache.bytes += bytes.len();
+
+        while cache.bytes > self.capacity {
+            match cache.lru.pop_lru() {
+                Some((_, evicted)) => cache.bytes -= evicted.len(),
+                None => break,
+            }
+        }
+    }
+}
+
+impl<B> fmt::Debug for CacheBackend<B>
+where
+    B: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CacheBackend")
+            .field("inner", &self.inner)
+            .field("capacity", &self.capacity)
+            .field("stats", &self.cache_stats())
+            .finish()
+    }
+}
+
+impl<B> Backend for CacheBackend<B>
+where
+    B: Backend,
+{
+    fn get(&self, hash: &IdHash, into: &mut [u8]) -> Result<(), CanonError> {
+        let generation = {
+            let mut cache = self.cache.lock();
+            // entries of another length are left to the inner backend to
+            // report, so that its statistics count them
+            if let Some(bytes) = cache.lru.get(hash) {
+                if bytes.len() == into.len() {
+                    self.hits.fetch_add(1, Ordering::Relaxed);
+                    into.copy_from_slice(bytes);
+                    return Ok(());
+                }
+            }
+            cache.generation
+        };
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        self.inner.get(hash, into)?;
+        self.cache(generation, *hash, into);
+        Ok(())
+    }
+
+    fn put(&self, hash: IdHash, bytes: &[u8]) {
+        let generation = self.generation();
+        self.inner.put(hash, bytes);
+        self.cache(generation, hash, bytes);
+    }
+
+    // The entry is uncached after it is removed from the inner backend, so a
+    // get reading it before can not cache it again
+    fn remove(&self, hash: &IdHash) -> Option<Vec<u8>> {
+        let removed = self.inner.remove(hash);
+
+        let mut cache = self.cache.lock();
+        if let Some(bytes) = cache.lru.pop(hash) {
+            cache.bytes -= bytes.len();
+        }
+        cache.generation += 1;
+
+        removed
+    }
+
+    fn put_many(&self, entries: &[(IdHash, &[u8])]) {
+        let generation = self.generation();
+        self.inner.put_many(entries);
+        for (hash, bytes) in entries {
+            self.cache(generation, *hash, bytes);
+        }
+    }
+
+    fn entries(&self) -> Vec<(IdHash, usize)> {
+        self.inner.entries()
+    }
+
+    fn largest(&self, n: usize) -> Vec<(IdHash, usize)> {
+        self.inner.largest(n)
+    }
+
+    // Gets served from the cache never reach the inner backend
+    fn stats(&self) -> StoreStats {
+        let stats = self.inner.stats();
+        StoreStats {
+            hits: stats.hits + self.hits.load(Ordering::Relaxed),
+            ..stats
+        }
+    }
+}
@@ -4,17 +4,24 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
-use blake2b_simd::Params;
+// Only part of the host store backs the mock host of the bridge
+#![cfg_attr(feature = "mock-bridge", allow(dead_code))]
 
 use std::collections::BTreeMap;
 
 use parking_lot::RwLock;
 
+use super::backend::{take_entry, Backend, MemoryBackend, StoreStats};
 use crate::canon::CanonError;
-use crate::id::{Id, IdHash};
+use crate::hash::HashConfig;
+use crate::id::{Id, IdHash, IdVersion};
 
 lazy_static::lazy_static! {
-    pub static ref STATIC_MAP: RwLock<BTreeMap<IdHash, Vec<u8>>> =
+    static ref GLOBAL: MemoryBackend = MemoryBackend::new();
+    static ref HASH_CONFIG: RwLock<HashConfig> =
+        RwLock::new(HashConfig::DEFAULT);
+    // Every configuration with a domain set, by domain id
+    static ref DOMAINS: RwLock<BTreeMap<u8, HashConfig>> =
         RwLock::new(BTreeMap::new());
 }
 
@@ -25,36 +32,105 @@ impl HostStore {
         hash: &IdHash,
         into: &mut [u8],
     ) -> Result<(), CanonError> {
-        match STATIC_MAP
-            .read()
-            .get(hash)
-            .map(|vec| into.copy_from_slice(vec))
-        {
-            Some(()) => Ok(()),
-            None => Err(CanonError::NotFound),
-        }
+        GLOBAL.get(hash, into)
+    }
+
+    pub(crate) fn get_many(
+        hashes: &[IdHash],
+        into: &mut [&mut [u8]],
+    ) -> Result<(), CanonError> {
+        GLOBAL.get_many(hashes, into)
     }
 
     pub(crate) fn put(bytes: &[u8]) -> IdHash {
-        let hash = Self::hash(bytes);
-        STATIC_MAP.write().insert(hash, Vec::from(bytes));
-        hash
+        Self::put_versioned(bytes).0
+    }
+
+    // The configuration is read once, so that a concurrent change can not
+    // give the hash and the version of an Id different configurations
+    pub(crate) fn put_versioned(bytes: &[u8]) -> (IdHash, IdVersion) {
+        let (hash, version) = Self::hash_versioned(bytes);
+        GLOBAL.put(hash, bytes);
+        (hash, version)
+    }
+
+    // Stores bytes under a hash computed elsewhere, possibly with another
+    // hash configuration
+    pub(crate) fn insert(hash: IdHash, bytes: &[u8]) {
+        GLOBAL.put(hash, bytes);
+    }
+
+    pub(crate) fn put_many(blobs: &[&[u8]]) -> Vec<IdHash> {
+        let versioned = Self::put_many_versioned(blobs);
+        versioned.into_iter().map(|(hash, _)| hash).collect()
+    }
+
+    pub(crate) fn put_many_versioned(
+        blobs: &[&[u8]],
+    ) -> Vec<(IdHash, IdVersion)> {
+        let config = Self::hash_config();
+        let entries: Vec<_> = blobs
+            .iter()
+            .map(|bytes| (config.hash(bytes), *bytes))
+            .collect();
+
+        GLOBAL.put_many(&entries);
+        let version = config.id_version();
+        entries
+            .into_iter()
+            .map(|(hash, _)| (hash, version))
+            .collect()
     }
 
     pub(crate) fn hash(bytes: &[u8]) -> IdHash {
-        let mut state = Params::new().hash_length(32).to_state();
-        state.update(bytes);
+        HASH_CONFIG.read().hash(bytes)
+    }
 
-        let mut buf = [0u8; 32];
-        buf.copy_from_slice(state.finalize().as_ref());
-        buf
+    pub(crate) fn hash_versioned(bytes: &[u8]) -> (IdHash, IdVersion) {
+        let config = Self::hash_config();
+        (config.hash(bytes), config.id_version())
     }
 
-    pub(crate) fn take_bytes(id: &Id) -> Result<Vec<u8>, CanonError> {
-        match STATIC_MAP.write().remove(&id.hash()) {
-            Some(vec) if id.size() == vec.len() => Ok(vec),
-            Some(_) => Err(CanonError::InvalidEncoding),
-            None => Err(CanonError::NotFound),
+    pub(crate) fn hash_config() -> HashConfig {
+        *HASH_CONFIG.read()
+    }
+
+    pub(crate) fn set_hash_config(config: HashConfig) {
+        if !config.domain.is_empty() {
+            assert!(config.domain_id < 0x80, "domain ids are below 128");
+            let mut domains = DOMAINS.write();
+            let known = domains.entry(config.domain_id).or_insert(config);
+            assert!(
+                *known == config,
+                "domain id {} is already used by {:?}",
+                config.domain_id,
+                known
+            );
         }
+        *HASH_CONFIG.write() = config;
+    }
+
+    // The configuration recorded by an Id version
+    pub(crate) fn config_of(version: IdVersion) -> Option<HashConfig> {
+        match version {
+            IdVersion::Domain(id) => DOMAINS.read().get(&id).copied(),
+            version => version.algorithm().map(HashConfig::new),
+        }
+    }
+
+    pub(crate) fn stats() -> StoreStats {
+        GLOBAL.stats()
+    }
+
+    pub(crate) fn entries() -> Vec<(IdHash, usize)> {
+        GLOBAL.entries()
+    }
+
+    pub(crate) fn largest(n: usize) -> Vec<(IdHash, usize)> {
+        GLOBAL.largest(n)
+    }
+
+    pub(crate) fn take_bytes(id: &Id) -> Result<Vec<u8>, CanonError> {
+        take_entry(&*GLOBAL, id)
     }
 }
@@ -0,0 +1,239 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! In-process mock of the host side of the `canon` import module
+//!
+//! With the `mock-bridge` feature, the bridge store used on wasm replaces the
+//! host store on native targets, and calls into this mock instead of the
+//! imports. The mock is backed by the host store, so the whole test suite can
+//! run against both stores, and counts the calls made to each import so tests
+//! can check how the bridge uses them.
+
+use core::cell::Cell;
+#[cfg(not(feature = "legacy-abi"))]
+use core::convert::TryFrom;
+use core::slice;
+
+use super::host::HostStore;
+#[cfg(not(feature = "legacy-abi"))]
+use crate::abi;
+use crate::id::IdHash;
+#[cfg(not(feature = "legacy-abi"))]
+use crate::id::IdVersion;
+
+/// The number of calls made to each import of the mock host
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ImportCalls {
+    /// Calls to `put`
+    pub put: usize,
+    /// Calls to `get`
+    pub get: usize,
+    /// Calls to `hash`
+    pub hash: usize,
+    /// Calls to `hash_version`
+    pub hash_version: usize,
+    /// Calls to `hash_with`
+    pub hash_with: usize,
+    /// Calls to `put_many`
+    pub put_many: usize,
+    /// Calls to `get_many`
+    pub get_many: usize,
+}
+
+// Counted per thread, since tests run in parallel
+thread_local! {
+    static CALLS: Cell<ImportCalls> = Cell::new(ImportCalls::default());
+}
+
+/// Returns the number of import calls made by the current thread
+pub fn calls() -> ImportCalls {
+    CALLS.with(Cell::get)
+}
+
+/// Resets the import call counts of the current thread
+pub fn reset_calls() {
+    CALLS.with(|calls| calls.set(ImportCalls::default()))
+}
+
+fn record(f: impl FnOnce(&mut ImportCalls)) {
+    CALLS.with(|calls| {
+        let mut counts = calls.get();
+        f(&mut counts);
+        calls.set(counts);
+    })
+}
+
+// The imports have the signatures of the `canon` import module, and the same
+// contract: pointers passed along with a length of zero may dangle.
+
+unsafe fn bytes<'a>(buf: *const u8, len: usize) -> &'a [u8] {
+    match len {
+        0 => &[],
+        len => slice::from_raw_parts(buf, len),
+    }
+}
+
+unsafe fn bytes_mut<'a>(buf: *mut u8, len: usize) -> &'a mut [u8] {
+    match len {
+        0 => &mut [],
+        len => slice::from_raw_parts_mut(buf, len),
+    }
+}
+
+#[cfg(not(feature = "legacy-abi"))]
+pub(crate) unsafe fn put(
+    buf: *const u8,
+    len: i32,
+    ret_hash: &mut IdHash,
+) -> i32 {
+    record(|calls| calls.put += 1);
+    let (hash, version) = HostStore::put_versioned(bytes(buf, len as usize));
+    *ret_hash = hash;
+    version.byte() as i32
+}
+
+#[cfg(not(feature = "legacy-abi"))]
+pub(crate) unsafe fn hash(
+    buf: *const u8,
+    len: i32,
+    ret_hash: &mut IdHash,
+) -> i32 {
+    record(|calls| calls.hash += 1);
+    let (hash, version) = HostStore::hash_versioned(bytes(buf, len as usize));
+    *ret_hash = hash;
+    version.byte() as i32
+}
+
+// The imports of version 0 of the ABI return nothing
+#[cfg(feature = "legacy-abi")]
+pub(crate) unsafe fn put(buf: *const u8, len: i32, ret_hash: &mut IdHash) {
+    record(|calls| calls.put += 1);
+    *ret_hash = HostStore::put(bytes(buf, len as usize));
+}
+
+#[cfg(feature = "legacy-abi")]
+pub(crate) unsafe fn hash(buf: *const u8, len: i32, ret_hash: &mut IdHash) {
+    record(|calls| calls.hash += 1);
+    *ret_hash = HostStore::hash(bytes(buf, len as usize));
+}
+
+#[cfg(not(feature = "legacy-abi"))]
+pub(crate) unsafe fn hash_version() -> i32 {
+    record(|calls| calls.hash_version += 1);
+    HostStore::hash_config().id_version().byte() as i32
+}
+
+#[cfg(not(feature = "legacy-abi"))]
+pub(crate) unsafe fn hash_with(
+    version: i32,
+    buf: *const u8,
+    len: i32,
+    ret_hash: &mut IdHash,
+) -> i32 {
+    record(|calls| calls.hash_with += 1);
+    let config = u8::try_from(version)
+        .ok()
+        .and_then(IdVersion::from_byte)
+        .and_then(HostStore::config_of);
+    match config {
+        Some(config) => {
+            *ret_hash = config.hash(bytes(buf, len as usize));
+            abi::OK
+        }
+        None => abi::NOT_FOUND,
+    }
+}
+
+#[cfg(not(feature = "legacy-abi"))]
+pub(crate) unsafe fn get(hash: &IdHash, buf: *mut u8, len: i32) -> i32 {
+    record(|calls| calls.get += 1);
+    abi::status(HostStore::get(hash, bytes_mut(buf, len as usize)))
+}
+
+// Hosts of version 0 of the ABI abort on failure
+#[cfg(feature = "legacy-abi")]
+pub(crate) unsafe fn get(hash: &IdHash, buf: *mut u8, len: i32) {
+    record(|calls| calls.get += 1);
+    if let Err(e) = HostStore::get(hash, bytes_mut(buf, len as usize)) {
+        panic!("host aborted on get: {:?}", e);
+    }
+}
+
+#[cfg(not(feature = "legacy-abi"))]
+unsafe fn lens<'a>(lens: *const i32, count: i32) -> &'a [i32] {
+    match count {
+        0 => &[],
+        count => slice::from_raw_parts(lens, count as usize),
+    }
+}
+
+// The total length of a batch. The lengths can not address more than the
+// memory of the module, so the host traps if they are negative or their sum
+// overflows.
+#[cfg(not(feature = "legacy-abi"))]
+fn total(lens: &[i32]) -> usize {
+    lens.iter()
+        .try_fold(0usize, |total, len| {
+            usize::try_from(*len)
+                .ok()
+                .and_then(|len| total.checked_add(len))
+        })
+        .expect("host trapped on the lengths of a batch")
+}
+
+#[cfg(not(feature = "legacy-abi"))]
+pub(crate) unsafe fn put_many(
+    bufs: *const u8,
+    lens_ptr: *const i32,
+    count: i32,
+    ret_hashes: *mut IdHash,
+) -> i32 {
+    record(|calls| calls.put_many += 1);
+    let lens = lens(lens_ptr, count);
+    let bytes = bytes(bufs, total(lens));
+
+    let mut blobs = Vec::with_capacity(lens.len());
+    let mut offset = 0;
+    for len in lens {
+        let len = *len as usize;
+        blobs.push(&bytes[offset..offset + len]);
+        offset += len;
+    }
+
+    // an empty batch is hashed for the current configuration
+    let versioned = HostStore::put_many_versioned(&blobs);
+    let version = versioned
+        .first()
+        .map_or_else(|| HostStore::hash_config().id_version(), |v| v.1);
+    for (i, (hash, _)) in versioned.into_iter().enumerate() {
+        *ret_hashes.add(i) = hash;
+    }
+    version.byte() as i32
+}
+
+#[cfg(not(feature = "legacy-abi"))]
+pub(crate) unsafe fn get_many(
+    hashes: *const IdHash,
+    lens_ptr: *const i32,
+    count: i32,
+    bufs: *mut u8,
+) -> i32 {
+    record(|calls| calls.get_many += 1);
+    let lens = lens(lens_ptr, count);
+    let hashes = match count {
+        0 => &[],
+        count => slice::from_raw_parts(hashes, count as usize),
+    };
+    let mut rest = bytes_mut(bufs, total(lens));
+
+    let mut into = Vec::with_capacity(lens.len());
+    for len in lens {
+        let (head, tail) = rest.split_at_mut(*len as usize);
+        into.push(head);
+        rest = tail;
+    }
+    abi::status(HostStore::get_many(hashes, &mut into))
+}
@@ -8,42 +8,215 @@ use cfg_if::cfg_if;
 
 use core::fmt;
 
-use crate::id::{Id, IdHash};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::hash::HashConfig;
+use crate::id::{Id, IdHash, IdVersion};
 use crate::CanonError;
 use alloc::vec::Vec;
 
 cfg_if! {
-    if #[cfg(target_arch = "wasm32")] {
+    if #[cfg(any(target_arch = "wasm32", feature = "mock-bridge"))] {
         mod bridge;
         use bridge::BridgeStore as Inner;
     } else {
-        mod host;
         use host::HostStore as Inner;
     }
 }
 
+#[cfg(all(not(target_arch = "wasm32"), feature = "async"))]
+mod async_backend;
+#[cfg(not(target_arch = "wasm32"))]
+mod backend;
+#[cfg(not(target_arch = "wasm32"))]
+mod cache;
+#[cfg(not(target_arch = "wasm32"))]
+mod host;
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "async"))]
+pub use async_backend::AsyncBackend;
+#[cfg(not(target_arch = "wasm32"))]
+pub use backend::{Backend, MemoryBackend, StoreRef, StoreStats};
+#[cfg(not(target_arch = "wasm32"))]
+pub use cache::{CacheBackend, CacheStats};
+#[cfg(all(not(target_arch = "wasm32"), feature = "mock-bridge"))]
+pub mod mock;
+
 /// Low-level interface to the store logic
+///
+/// Outside of wasm, the store used can be changed for the current thread with
+/// `Store::scoped`, and defaults to a global in-memory store.
 pub struct Store;
 
+// Dispatches to the scoped store of the current thread, if any
+macro_rules! scoped {
+    ($store:ident => $scoped:expr) => {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some($store) = backend::scoped() {
+            return $scoped;
+        }
+    };
+}
+
 impl Store {
     /// Write the byte slice into the store and return its hash
     pub fn put(bytes: &[u8]) -> IdHash {
+        scoped!(store => store.put(bytes));
         Inner::put(bytes)
     }
 
     /// Get data with the corresponding hash and write it to a buffer
     ///
-    /// Note that the buffer must be of the right length to accept the data
+    /// Note that the buffer must be of the right length to accept the data,
+    /// otherwise `CanonError::Corrupted` is returned
     pub fn get(hash: &IdHash, write_to: &mut [u8]) -> Result<(), CanonError> {
+        scoped!(store => store.get(hash, write_to));
         Inner::get(hash, write_to)
     }
 
+    // Writes the byte slice into the store, returning its hash and the
+    // version of its Id, both from the same hash configuration
+    pub(crate) fn put_versioned(bytes: &[u8]) -> (IdHash, IdVersion) {
+        scoped!(store => store.put_versioned(bytes));
+        Inner::put_versioned(bytes)
+    }
+
+    /// Write several byte slices into the store at once, returning their
+    /// hashes in order
+    ///
+    /// This is cheaper than repeated calls to `put`, since the store is only
+    /// locked once, and the bridge makes a single call to the host.
+    pub fn put_many(blobs: &[&[u8]]) -> Vec<IdHash> {
+        scoped!(store => store.put_many(blobs));
+        Inner::put_many(blobs)
+    }
+
+    // As `put_versioned`, for several byte slices
+    pub(crate) fn put_many_versioned(
+        blobs: &[&[u8]],
+    ) -> Vec<(IdHash, IdVersion)> {
+        scoped!(store => store.put_many_versioned(blobs));
+        Inner::put_many_versioned(blobs)
+    }
+
+    /// Get the data of several hashes at once, writing each to the buffer at
+    /// the same position in `write_to`
+    ///
+    /// As with `get`, each buffer must be of the right length to accept the
+    /// data. Returns the first error encountered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hashes` and `write_to` differ in length.
+    pub fn get_many(
+        hashes: &[IdHash],
+        write_to: &mut [&mut [u8]],
+    ) -> Result<(), CanonError> {
+        assert_eq!(hashes.len(), write_to.len(), "one buffer per hash");
+        scoped!(store => store.get_many(hashes, write_to));
+        Inner::get_many(hashes, write_to)
+    }
+
+    /// Runs `f` with `Store` using the given store on the current thread
+    ///
+    /// Scopes nest, and the previous store is restored when `f` returns or
+    /// panics. Note that the hash configuration is shared by all stores.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn scoped<S, R>(store: S, f: impl FnOnce() -> R) -> R
+    where
+        S: Into<StoreRef>,
+    {
+        backend::scope(store.into(), f)
+    }
+
+    /// Returns statistics on the entries of the store used on the current
+    /// thread and its use
+    ///
+    /// With the `mock-bridge` feature, these are the statistics of the store
+    /// of the mock host.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn stats() -> StoreStats {
+        StoreRef::current().stats()
+    }
+
+    /// Returns an iterator over the hash and size of each entry in the store
+    /// used on the current thread
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn entries() -> impl Iterator<Item = (IdHash, usize)> {
+        StoreRef::current().entries()
+    }
+
+    /// Returns the hash and size of the `n` largest entries in the store
+    /// used on the current thread, largest first
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn largest(n: usize) -> Vec<(IdHash, usize)> {
+        StoreRef::current().largest(n)
+    }
+
     /// Hash a slice of bytes
     pub fn hash(bytes: &[u8]) -> IdHash {
         Inner::hash(bytes)
     }
 
+    /// Returns the hash configuration used by the host store
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn hash_config() -> HashConfig {
+        host::HostStore::hash_config()
+    }
+
+    /// Sets the hash configuration used by the host store
+    ///
+    /// Entries already in the store keep the hashes they were put with, and
+    /// their `Id`s keep verifying: configurations with a domain are
+    /// remembered by their `domain_id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `domain_id` of `config` is not below 128, or was set
+    /// before with another algorithm or domain.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_hash_config(config: HashConfig) {
+        host::HostStore::set_hash_config(config)
+    }
+
+    // The version of the Ids hashed by the store, as reported by the host on
+    // the bridge
+    pub(crate) fn id_version() -> IdVersion {
+        cfg_if! {
+            if #[cfg(any(target_arch = "wasm32", feature = "mock-bridge"))] {
+                Inner::id_version()
+            } else {
+                Inner::hash_config().id_version()
+            }
+        }
+    }
+
+    // Hash a slice of bytes, returning the version of its Id along with the
+    // hash, both from the same hash configuration
+    #[cfg(all(
+        not(target_arch = "wasm32"),
+        any(feature = "async", feature = "imports")
+    ))]
+    pub(crate) fn hash_versioned(bytes: &[u8]) -> (IdHash, IdVersion) {
+        Inner::hash_versioned(bytes)
+    }
+
+    // Hash as recorded by the version of an Id, if the configuration is
+    // known
+    pub(crate) fn hash_with(
+        version: IdVersion,
+        bytes: &[u8],
+    ) -> Option<IdHash> {
+        cfg_if! {
+            if #[cfg(any(target_arch = "wasm32", feature = "mock-bridge"))] {
+                Inner::hash_with(version, bytes)
+            } else {
+                Inner::config_of(version).map(|config| config.hash(bytes))
+            }
+        }
+    }
+
     pub(crate) fn take_bytes(id: &Id) -> Result<Vec<u8>, CanonError> {
+        scoped!(store => store.take_bytes(id));
         Inner::take_bytes(id)
     }
 }
@@ -0,0 +1,541 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use core::convert::TryFrom;
+use core::fmt;
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::id;
+use crate::schema::{Endian, Fields, Schema};
+use crate::{Canon, CanonError, Id, Source};
+
+/// A decoded value of any `Canon` type, as described by a [`Schema`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    /// A value without contents
+    Unit,
+    /// A boolean
+    Bool(bool),
+    /// An unsigned integer
+    UInt(u128),
+    /// A signed integer
+    Int(i128),
+    /// A sequence or array of bytes
+    Bytes(Vec<u8>),
+    /// A string
+    String(String),
+    /// An optional value
+    Option(Option<Box<Value>>),
+    /// Either an ok value or an error
+    Result(Result<Box<Value>, Box<Value>>),
+    /// A sequence or array of values
+    Seq(Vec<Value>),
+    /// A tuple of values
+    Tuple(Vec<Value>),
+    /// Key-value pairs of a map
+    Map(Vec<(Value, Value)>),
+    /// A struct
+    Struct {
+        /// Name of the type
+        name: String,
+        /// Values of the fields
+        fields: FieldValues,
+    },
+    /// A variant of an enum
+    Variant {
+        /// Name of the enum type
+        name: String,
+        /// Name of the variant
+        variant: String,
+        /// Tag byte of the variant
+        tag: u8,
+        /// Values of the fields
+        fields: FieldValues,
+    },
+    /// An identifier of another value in the store
+    Id(Id),
+}
+
+/// Decoded fields of a struct or an enum variant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldValues {
+    /// Fields with names
+    Named(Vec<(String, Value)>),
+    /// Positional fields
+    Unnamed(Vec<Value>),
+    /// No fields
+    Unit,
+}
+
+impl Value {
+    /// Decodes a value described by `schema` from a `Source`
+    ///
+    /// Unlike `Canon::decode`, running out of bytes is reported as
+    /// `CanonError::InvalidEncoding` instead of panicking, since the schema
+    /// might not match the bytes. At most 2^16 items that can be encoded in
+    /// zero bytes, such as `()`, are decoded in all the sequences, maps and
+    /// arrays of the value, since their number can not be checked against
+    /// the bytes.
+    pub fn decode(
+        schema: &Schema,
+        source: &mut Source,
+    ) -> Result<Self, CanonError> {
+        let mut empty = MAX_EMPTY_ITEMS;
+        Value::decode_within(schema, source, &mut empty)
+    }
+
+    // Decodes a value, taking the items encoded in zero bytes it holds from
+    // the `empty` items left to the whole decoding
+    fn decode_within(
+        schema: &Schema,
+        source: &mut Source,
+        empty: &mut usize,
+    ) -> Result<Self, CanonError> {
+        Ok(match schema {
+            Schema::Unit => Value::Unit,
+            Schema::Bool => Value::Bool(bool::decode(take(source, 1)?)?),
+            Schema::U8 => Value::UInt(take(source, 1)?.read_bytes(1)[0].into()),
+            Schema::Varint { bits, signed } => {
+                decode_varint(*bits, *signed, source)?
+            }
+            Schema::Fixed {
+                bits,
+                signed,
+                endian,
+            } => decode_fixed(*bits, *signed, *endian, source)?,
+            Schema::String => {
+                let len = decode_len(source)?;
+                let bytes = take(source, len)?.read_bytes(len);
+                let string = core::str::from_utf8(bytes)
+                    .map_err(|_| CanonError::InvalidEncoding)?;
+                Value::String(string.into())
+            }
+            Schema::Option(some) => match take(source, 1)?.read_bytes(1) {
+                [0] => Value::Option(None),
+                [1] => {
+                    let some = Value::decode_within(some, source, empty)?;
+                    Value::Option(Some(Box::new(some)))
+                }
+                _ => return Err(CanonError::InvalidEncoding),
+            },
+            Schema::Result(ok, err) => match take(source, 1)?.read_bytes(1) {
+                [0] => {
+                    let ok = Value::decode_within(ok, source, empty)?;
+                    Value::Result(Ok(Box::new(ok)))
+                }
+                [1] => {
+                    let err = Value::decode_within(err, source, empty)?;
+                    Value::Result(Err(Box::new(err)))
+                }
+                _ => return Err(CanonError::InvalidEncoding),
+            },
+            Schema::Sequence(item) => {
+                let len = decode_len(source)?;
+                decode_items(item, len, source, empty)?
+            }
+            Schema::Map(key, value) => {
+                let len = decode_len(source)?;
+                check_room(source, len, min_len(key) + min_len(value), empty)?;
+                let mut pairs = Vec::new();
+                for _ in 0..len {
+                    let k = Value::decode_within(key, source, empty)?;
+                    let v = Value::decode_within(value, source, empty)?;
+                    pairs.push((k, v));
+                }
+                Value::Map(pairs)
+            }
+            Schema::Array(len, item) => {
+                let len = usize::try_from(*len)
+                    .map_err(|_| CanonError::InvalidEncoding)?;
+                decode_items(item, len, source, empty)?
+            }
+            Schema::Tuple(items) => Value::Tuple(
+                items
+                    .iter()
+                    .map(|item| Value::decode_within(item, source, empty))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Schema::Struct { name, fields } => Value::Struct {
+                name: name.clone(),
+                fields: FieldValues::decode(fields, source, empty)?,
+            },
+            Schema::Enum { name, variants } => {
+                let tag = take(source, 1)?.read_bytes(1)[0];
+                let variant = variants
+                    .iter()
+                    .find(|variant| variant.tag == tag)
+                    .ok_or(CanonError::InvalidEncoding)?;
+                Value::Variant {
+                    name: name.clone(),
+                    variant: variant.name.clone(),
+                    tag,
+                    fields: FieldValues::decode(
+                        &variant.fields,
+                        source,
+                        empty,
+                    )?,
+                }
+            }
+            Schema::Id => {
+                // a version byte and a varint length precede the hash, or
+                // the bytes of an inline id
+                take(source, 1)?;
+                let version = source.bytes[source.offset];
+                let mut rest = Source::new(&source.bytes[source.offset + 1..]);
+                check_varint(&rest, 1)?;
+                let len = u32::decode(&mut rest)?;
+                let body = id::body_len(version, len)?;
+                take(source, 1 + rest.offset + body)?;
+                Value::Id(Id::decode(source)?)
+            }
+        })
+    }
+
+    /// Decodes a value described by `schema` from a complete byte slice
+    ///
+    /// Trailing bytes are an error, as they indicate a mismatched schema.
+    pub fn from_bytes(
+        schema: &Schema,
+        bytes: &[u8],
+    ) -> Result<Self, CanonError> {
+        let mut source = Source::new(bytes);
+        let value = Value::decode(schema, &mut source)?;
+        match source.offset == bytes.len() {
+            true => Ok(value),
+            false => Err(CanonError::InvalidEncoding),
+        }
+    }
+
+    /// Decodes the value identified by `id` from the store
+    pub fn reify(id: &Id, schema: &Schema) -> Result<Self, CanonError> {
+        Value::from_bytes(schema, &id.fetch_bytes()?)
+    }
+}
+
+impl FieldValues {
+    fn decode(
+        fields: &Fields,
+        source: &mut Source,
+        empty: &mut usize,
+    ) -> Result<Self, CanonError> {
+        Ok(match fields {
+            Fields::Named(fields) => FieldValues::Named(
+                fields
+                    .iter()
+                    .map(|(name, schema)| {
+                        let value =
+                            Value::decode_within(schema, source, empty)?;
+                        Ok((name.clone(), value))
+                    })
+                    .collect::<Result<_, CanonError>>()?,
+            ),
+            Fields::Unnamed(fields) => FieldValues::Unnamed(
+                fields
+                    .iter()
+                    .map(|schema| Value::decode_within(schema, source, empty))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Fields::Unit => FieldValues::Unit,
+        })
+    }
+}
+
+// Makes sure `n` more bytes are available, returning the source for chaining
+fn take<'a, 'b>(
+    source: &'a mut Source<'b>,
+    n: usize,
+) -> Result<&'a mut Source<'b>, CanonError> {
+    match source.bytes.len() - source.offset >= n {
+        true => Ok(source),
+        false => Err(CanonError::InvalidEncoding),
+    }
+}
+
+// Makes sure `count` varints are terminated within the available bytes,
+// looking no further than the end of the last of them
+fn check_varint(source: &Source, count: usize) -> Result<(), CanonError> {
+    const MSB: u8 = 0b1000_0000;
+    let ends = source.bytes[source.offset..]
+        .iter()
+        .filter(|b| *b & MSB == 0)
+        .take(count)
+        .count();
+    match ends >= count {
+        true => Ok(()),
+        false => Err(CanonError::InvalidEncoding),
+    }
+}
+
+// The most items of schemas encoded in zero bytes decoded by one call to
+// `Value::decode`
+const MAX_EMPTY_ITEMS: usize = 1 << 16;
+
+// Makes sure `len` items of at least `min` bytes each can be present. Items
+// of zero bytes are taken from the `empty` items left instead.
+fn check_room(
+    source: &Source,
+    len: usize,
+    min: usize,
+    empty: &mut usize,
+) -> Result<(), CanonError> {
+    if min == 0 {
+        *empty = empty.checked_sub(len).ok_or(CanonError::InvalidEncoding)?;
+    }
+    match len.checked_mul(min) {
+        Some(needed) if needed <= source.bytes.len() - source.offset => Ok(()),
+        _ => Err(CanonError::InvalidEncoding),
+    }
+}
+
+fn decode_len(source: &mut Source) -> Result<usize, CanonError> {
+    check_varint(source, 1)?;
+    let len = u64::decode(source)?;
+    usize::try_from(len).map_err(|_| CanonError::InvalidEncoding)
+}
+
+fn decode_items(
+    item: &Schema,
+    len: usize,
+    source: &mut Source,
+    empty: &mut usize,
+) -> Result<Value, CanonError> {
+    check_room(source, len, min_len(item), empty)?;
+    if let Schema::U8 = item {
+        return Ok(Value::Bytes(source.read_bytes(len).into()));
+    }
+    let mut items = Vec::new();
+    for _ in 0..len {
+        items.push(Value::decode_within(item, source, empty)?);
+    }
+    Ok(Value::Seq(items))
+}
+
+fn decode_varint(
+    bits: u8,
+    signed: bool,
+    source: &mut Source,
+) -> Result<Value, CanonError> {
+    // 128 bit integers are encoded as two varints
+    check_varint(source, if bits > 64 { 2 } else { 1 })?;
+    Ok(match (bits, signed) {
+        (16, false) => Value::UInt(u16::decode(source)?.into()),
+        (16, true) => Value::Int(i16::decode(source)?.into()),
+        (32, false) => Value::UInt(u32::decode(source)?.into()),
+        (32, true) => Value::Int(i32::decode(source)?.into()),
+        (64, false) => Value::UInt(u64::decode(source)?.into()),
+        (64, true) => Value::Int(i64::decode(source)?.into()),
+        (128, false) => Value::UInt(u128::decode(source)?),
+        (128, true) => Value::Int(i128::decode(source)?),
+        _ => return Err(CanonError::InvalidEncoding),
+    })
+}
+
+fn decode_fixed(
+    bits: u8,
+    signed: bool,
+    endian: Endian,
+    source: &mut Source,
+) -> Result<Value, CanonError> {
+    if bits == 0 || bits > 128 || !bits.is_multiple_of(8) {
+        return Err(CanonError::InvalidEncoding);
+    }
+    let len = bits as usize / 8;
+    let bytes = take(source, len)?.read_bytes(len);
+
+    let mut buf = [0u8; 16];
+    match endian {
+        Endian::Little => buf[..len].copy_from_slice(bytes),
+        Endian::Big => buf[..len]
+            .iter_mut()
+            .zip(bytes.iter().rev())
+            .for_each(|(b, byte)| *b = *byte),
+    }
+    let unsigned = u128::from_le_bytes(buf);
+
+    Ok(match signed {
+        false => Value::UInt(unsigned),
+        true => {
+            // sign extend from the encoded width
+            let shift = 128 - bits as u32;
+            Value::Int(((unsigned << shift) as i128) >> shift)
+        }
+    })
+}
+
+// The least number of bytes a value of the schema can be encoded in
+fn min_len(schema: &Schema) -> usize {
+    match schema {
+        Schema::Unit => 0,
+        Schema::Bool | Schema::U8 => 1,
+        Schema::Varint { bits, .. } => {
+            if *bits > 64 {
+                2
+            } else {
+                1
+            }
+        }
+        Schema::Fixed { bits, .. } => *bits as usize / 8,
+        Schema::String
+        | Schema::Option(_)
+        | Schema::Result(..)
+        | Schema::Sequence(_)
+        | Schema::Map(..)
+        | Schema::Enum { .. } => 1,
+        Schema::Array(len, item) => {
+            (*len as usize).saturating_mul(min_len(item))
+        }
+        Schema::Tuple(items) => items.iter().map(min_len).sum(),
+        Schema::Struct { fields, .. } => match fields {
+            Fields::Named(fields) => {
+                fields.iter().map(|(_, schema)| min_len(schema)).sum()
+            }
+            Fields::Unnamed(fields) => fields.iter().map(min_len).sum(),
+            Fields::Unit => 0,
+        },
+        Schema::Id => 1 + 1,
+    }
+}
+
+struct Printer<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    indent: usize,
+}
+
+impl<'a, 'b> Printer<'a, 'b> {
+    fn pretty(&self) -> bool {
+        self.f.alternate()
+    }
+
+    fn newline(&mut self) -> fmt::Result {
+        write!(self.f, "\n{:1$}", "", self.indent * 4)
+    }
+
+    // Writes a delimited list of entries, one per line in alternate mode
+    fn list<I, F>(
+        &mut self,
+        open: &str,
+        close: &str,
+        entries: I,
+        mut entry: F,
+    ) -> fmt::Result
+    where
+        I: ExactSizeIterator,
+        F: FnMut(&mut Self, I::Item) -> fmt::Result,
+    {
+        self.f.write_str(open)?;
+        let len = entries.len();
+        if len == 0 {
+            return self.f.write_str(close);
+        }
+        self.indent += 1;
+        for (i, item) in entries.enumerate() {
+            if self.pretty() {
+                self.newline()?;
+            } else if i > 0 {
+                self.f.write_str(", ")?;
+            }
+            entry(self, item)?;
+            if self.pretty() {
+                self.f.write_str(",")?;
+            }
+        }
+        self.indent -= 1;
+        if self.pretty() {
+            self.newline()?;
+        }
+        self.f.write_str(close)
+    }
+
+    fn fields(&mut self, fields: &FieldValues) -> fmt::Result {
+        match fields {
+            FieldValues::Named(fields) if fields.is_empty() => {
+                self.f.write_str(" {}")
+            }
+            FieldValues::Named(fields) => {
+                let (open, close) = match self.pretty() {
+                    true => (" {", "}"),
+                    false => (" { ", " }"),
+                };
+                self.list(open, close, fields.iter(), |p, (name, value)| {
+                    write!(p.f, "{}: ", name)?;
+                    p.value(value)
+                })
+            }
+            FieldValues::Unnamed(fields) => {
+                self.list("(", ")", fields.iter(), |p, value| p.value(value))
+            }
+            FieldValues::Unit => Ok(()),
+        }
+    }
+
+    fn value(&mut self, value: &Value) -> fmt::Result {
+        match value {
+            Value::Unit => self.f.write_str("()"),
+            Value::Bool(b) => write!(self.f, "{}", b),
+            Value::UInt(int) => write!(self.f, "{}", int),
+            Value::Int(int) => write!(self.f, "{}", int),
+            Value::Bytes(bytes) => {
+                self.f.write_str("0x")?;
+                bytes.iter().try_for_each(|b| write!(self.f, "{:02x}", b))
+            }
+            Value::String(string) => write!(self.f, "{:?}", string),
+            Value::Option(None) => self.f.write_str("None"),
+            Value::Option(Some(value)) => {
+                self.list("Some(", ")", core::iter::once(value), |p, v| {
+                    p.value(v)
+                })
+            }
+            Value::Result(Ok(value)) => {
+                self.list("Ok(", ")", core::iter::once(value), |p, v| {
+                    p.value(v)
+                })
+            }
+            Value::Result(Err(value)) => {
+                self.list("Err(", ")", core::iter::once(value), |p, v| {
+                    p.value(v)
+                })
+            }
+            Value::Seq(items) => {
+                self.list("[", "]", items.iter(), |p, v| p.value(v))
+            }
+            Value::Tuple(items) => {
+                self.list("(", ")", items.iter(), |p, v| p.value(v))
+            }
+            Value::Map(pairs) => {
+                self.list("{", "}", pairs.iter(), |p, (k, v)| {
+                    p.value(k)?;
+                    p.f.write_str(": ")?;
+                    p.value(v)
+                })
+            }
+            Value::Struct { name, fields } => {
+                self.f.write_str(name)?;
+                self.fields(fields)
+            }
+            Value::Variant {
+                name,
+                variant,
+                fields,
+                ..
+            } => {
+                write!(self.f, "{}::{}", name, variant)?;
+                self.fields(fields)
+            }
+            Value::Id(id) => write!(self.f, "{:?}", id),
+        }
+    }
+}
+
+/// Prints the value in a Rust-like syntax, spread over indented lines when
+/// formatted with `{:#}`
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Printer { f, indent: 0 }.value(self)
+    }
+}
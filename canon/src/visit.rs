@@ -0,0 +1,346 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use core::cmp::{Ordering, Reverse};
+use core::marker::PhantomData;
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroU128, NonZeroU16,
+    NonZeroU32, NonZeroU64, NonZeroU8, Wrapping,
+};
+use core::ops::{Range, RangeInclusive};
+use core::time::Duration;
+
+use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::{Canon, CanonError, FixedBE, FixedLE, Id, Source, TypedId};
+
+/// Trait to enumerate the `Id`s held by a value
+///
+/// This is what allows walking the graph of values in a store, since stored
+/// bytes do not tell which `Id`s they contain.
+pub trait VisitIds {
+    /// Calls `visit` with each `Id` in the value, in encoding order
+    fn visit_ids(&self, visit: &mut dyn FnMut(&Id));
+
+    /// Returns the `Id`s in the value, in encoding order
+    fn ids(&self) -> Vec<Id> {
+        let mut ids = Vec::new();
+        self.visit_ids(&mut |id| ids.push(*id));
+        ids
+    }
+}
+
+/// Returns the `Id`s held by the value of type `T` encoded in `bytes`
+///
+/// Has the signature of the `children` functions taken by `Walk::new` and
+/// `archive::export`, for graphs where all values are of the same type.
+///
+/// Every value reached is decoded as a `T`, since neither an `Id` nor the
+/// bytes of its value record a type. In a graph holding values of several
+/// types, decoding a child of another type fails, or worse succeeds and
+/// yields `Id`s that are not in the value. Such graphs need a `children`
+/// function of their own, knowing the type of each `Id` from its parent.
+pub fn children_of<T>(_: &Id, bytes: &[u8]) -> Result<Vec<Id>, CanonError>
+where
+    T: Canon + VisitIds,
+{
+    Ok(T::decode(&mut Source::new(bytes))?.ids())
+}
+
+/// The order in which a `Walk` visits values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// Visit all children of a value before their own children
+    BreadthFirst,
+    /// Visit the children of a value, and theirs, before its next sibling
+    DepthFirst,
+}
+
+/// Iterator over the values reachable from a set of roots
+///
+/// Yields each `Id` once, with the bytes of its value, including inline
+/// `Id`s. The `children` function returns the `Id`s held by a value, given
+/// its `Id` and bytes. The store does not record the type of values, so the
+/// function has to know it, for example by keeping the types of the `Id`s
+/// returned for each parent.
+///
+/// Values are read from `Store`, so a store set with `Store::scoped` must be
+/// in scope while iterating. Values that can not be read, or whose children
+/// can not be found, are yielded as errors, and their children skipped.
+pub struct Walk<F> {
+    order: Order,
+    pending: VecDeque<Id>,
+    visited: BTreeSet<Id>,
+    children: F,
+}
+
+impl<F> Walk<F>
+where
+    F: FnMut(&Id, &[u8]) -> Result<Vec<Id>, CanonError>,
+{
+    /// Creates a walk from the given roots
+    pub fn new(roots: &[Id], order: Order, children: F) -> Self {
+        Walk {
+            order,
+            pending: roots.iter().copied().collect(),
+            visited: BTreeSet::new(),
+            children,
+        }
+    }
+}
+
+/// The type of `children_of`
+pub type ChildrenOf = fn(&Id, &[u8]) -> Result<Vec<Id>, CanonError>;
+
+impl Walk<ChildrenOf> {
+    /// Creates a walk from the given roots, all values being of type `T`
+    ///
+    /// Uses `children_of::<T>`, so every reachable value must be a `T`. Use
+    /// `Walk::new` for graphs holding values of several types.
+    pub fn of<T>(roots: &[Id], order: Order) -> Self
+    where
+        T: Canon + VisitIds,
+    {
+        Walk::new(roots, order, children_of::<T>)
+    }
+}
+
+impl<F> Iterator for Walk<F>
+where
+    F: FnMut(&Id, &[u8]) -> Result<Vec<Id>, CanonError>,
+{
+    type Item = Result<(Id, Vec<u8>), CanonError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = loop {
+            let id = self.pending.pop_front()?;
+            if self.visited.insert(id) {
+                break id;
+            }
+        };
+
+        let bytes = match id.fetch_bytes() {
+            Ok(bytes) => bytes,
+            Err(e) => return Some(Err(e)),
+        };
+        let children = match (self.children)(&id, &bytes) {
+            Ok(children) => children,
+            Err(e) => return Some(Err(e)),
+        };
+
+        match self.order {
+            Order::BreadthFirst => self.pending.extend(children),
+            Order::DepthFirst => {
+                for child in children.into_iter().rev() {
+                    self.pending.push_front(child);
+                }
+            }
+        }
+
+        Some(Ok((id, bytes)))
+    }
+}
+
+impl VisitIds for Id {
+    fn visit_ids(&self, visit: &mut dyn FnMut(&Id)) {
+        visit(self)
+    }
+}
+
+impl<T> VisitIds for TypedId<T> {
+    fn visit_ids(&self, visit: &mut dyn FnMut(&Id)) {
+        visit(&self.id())
+    }
+}
+
+macro_rules! leaf {
+    ($($ty:ty),*) => {
+        $(
+            impl VisitIds for $ty {
+                fn visit_ids(&self, _: &mut dyn FnMut(&Id)) {}
+            }
+        )*
+    };
+}
+
+leaf!(u8, u16, i16, u32, i32, u64, i64, u128, i128, bool, (), !);
+leaf!(NonZeroU8, NonZeroU16, NonZeroI16, NonZeroU32, NonZeroI32);
+leaf!(NonZeroU64, NonZeroI64, NonZeroU128, NonZeroI128);
+leaf!(Ordering, Duration, String, CanonError);
+
+impl<T> VisitIds for PhantomData<T> {
+    fn visit_ids(&self, _: &mut dyn FnMut(&Id)) {}
+}
+
+impl<T> VisitIds for FixedLE<T> {
+    fn visit_ids(&self, _: &mut dyn FnMut(&Id)) {}
+}
+
+impl<T> VisitIds for FixedBE<T> {
+    fn visit_ids(&self, _: &mut dyn FnMut(&Id)) {}
+}
+
+impl<T> VisitIds for Option<T>
+where
+    T: VisitIds,
+{
+    fn visit_ids(&self, visit: &mut dyn FnMut(&Id)) {
+        if let Some(t) = self {
+            t.visit_ids(visit)
+        }
+    }
+}
+
+impl<T, E> VisitIds for Result<T, E>
+where
+    T: VisitIds,
+    E: VisitIds,
+{
+    fn visit_ids(&self, visit: &mut dyn FnMut(&Id)) {
+        match self {
+            Ok(t) => t.visit_ids(visit),
+            Err(e) => e.visit_ids(visit),
+        }
+    }
+}
+
+impl<T> VisitIds for Wrapping<T>
+where
+    T: VisitIds,
+{
+    fn visit_ids(&self, visit: &mut dyn FnMut(&Id)) {
+        self.0.visit_ids(visit)
+    }
+}
+
+impl<T> VisitIds for Reverse<T>
+where
+    T: VisitIds,
+{
+    fn visit_ids(&self, visit: &mut dyn FnMut(&Id)) {
+        self.0.visit_ids(visit)
+    }
+}
+
+impl<T> VisitIds for Range<T>
+where
+    T: VisitIds,
+{
+    fn visit_ids(&self, visit: &mut dyn FnMut(&Id)) {
+        self.start.visit_ids(visit);
+        self.end.visit_ids(visit);
+    }
+}
+
+impl<T> VisitIds for RangeInclusive<T>
+where
+    T: VisitIds,
+{
+    fn visit_ids(&self, visit: &mut dyn FnMut(&Id)) {
+        self.start().visit_ids(visit);
+        self.end().visit_ids(visit);
+    }
+}
+
+macro_rules! tuple {
+    ( $($name:ident)+) => (
+        #[allow(non_snake_case)]
+        impl<$($name,)+> VisitIds for ($($name,)+)
+        where
+            $($name: VisitIds,)+
+        {
+            fn visit_ids(&self, visit: &mut dyn FnMut(&Id)) {
+                let ($(ref $name,)+) = *self;
+                $($name.visit_ids(visit);)+
+            }
+        }
+    );
+}
+
+tuple! { A }
+tuple! { A B }
+tuple! { A B C }
+tuple! { A B C D }
+tuple! { A B C D E }
+tuple! { A B C D E F }
+tuple! { A B C D E F G }
+tuple! { A B C D E F G H }
+tuple! { A B C D E F G H I }
+tuple! { A B C D E F G H I J }
+tuple! { A B C D E F G H I J K }
+tuple! { A B C D E F G H I J K L }
+tuple! { A B C D E F G H I J K L M }
+tuple! { A B C D E F G H I J K L M N }
+tuple! { A B C D E F G H I J K L M N O }
+tuple! { A B C D E F G H I J K L M N O P }
+
+impl<T, const N: usize> VisitIds for [T; N]
+where
+    T: VisitIds,
+{
+    fn visit_ids(&self, visit: &mut dyn FnMut(&Id)) {
+        for t in self {
+            t.visit_ids(visit);
+        }
+    }
+}
+
+impl<T> VisitIds for Vec<T>
+where
+    T: VisitIds,
+{
+    fn visit_ids(&self, visit: &mut dyn FnMut(&Id)) {
+        for t in self {
+            t.visit_ids(visit);
+        }
+    }
+}
+
+impl<T> VisitIds for BTreeSet<T>
+where
+    T: VisitIds,
+{
+    fn visit_ids(&self, visit: &mut dyn FnMut(&Id)) {
+        for t in self {
+            t.visit_ids(visit);
+        }
+    }
+}
+
+impl<K, V> VisitIds for BTreeMap<K, V>
+where
+    K: VisitIds,
+    V: VisitIds,
+{
+    fn visit_ids(&self, visit: &mut dyn FnMut(&Id)) {
+        for (k, v) in self {
+            k.visit_ids(visit);
+            v.visit_ids(visit);
+        }
+    }
+}
+
+impl<T> VisitIds for Rc<T>
+where
+    T: VisitIds,
+{
+    fn visit_ids(&self, visit: &mut dyn FnMut(&Id)) {
+        (**self).visit_ids(visit)
+    }
+}
+
+impl<T> VisitIds for Arc<T>
+where
+    T: VisitIds,
+{
+    fn visit_ids(&self, visit: &mut dyn FnMut(&Id)) {
+        (**self).visit_ids(visit)
+    }
+}
@@ -0,0 +1,36 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use canonical::{abi, CanonError, Store};
+
+#[test]
+fn status_round_trip() {
+    assert_eq!(abi::status(Ok(())), abi::OK);
+    assert_eq!(abi::status(Err(CanonError::NotFound)), abi::NOT_FOUND);
+    assert_eq!(abi::status(Err(CanonError::Corrupted)), abi::CORRUPTED);
+
+    for status in [abi::OK, abi::NOT_FOUND, abi::CORRUPTED] {
+        assert_eq!(abi::status(abi::result(status)), status);
+    }
+    assert!(matches!(abi::result(-1), Err(CanonError::Corrupted)));
+}
+
+#[test]
+fn store_status() {
+    let mut buf = [0u8; 40];
+    let hash = Store::put(&[1; 40]);
+    assert_eq!(abi::status(Store::get(&hash, &mut buf)), abi::OK);
+
+    #[cfg(not(all(feature = "mock-bridge", feature = "legacy-abi")))]
+    {
+        let missing = abi::status(Store::get(&[0xdd; 32], &mut buf));
+        assert_eq!(missing, abi::NOT_FOUND);
+        assert_eq!(
+            abi::status(Store::get(&hash, &mut buf[..39])),
+            abi::CORRUPTED
+        );
+    }
+}
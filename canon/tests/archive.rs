@@ -0,0 +1,212 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use canonical::archive::{self, Error};
+use canonical::{children_of, CanonError, Id, StoreRef};
+
+// A node of a tree, with a label and the ids of its children
+type Node = (u32, Vec<Id>);
+
+fn node(store: &StoreRef, label: u32, children: Vec<Id>) -> Id {
+    Id::new_in(&(label, children), store)
+}
+
+// Builds a tree sharing a subtree between two branches
+fn tree(store: &StoreRef) -> Id {
+    let shared = node(store, 1, vec![]);
+    let left = node(store, 2, vec![shared]);
+    let right = node(store, 3, vec![shared, node(store, 4, vec![])]);
+    node(store, 0, vec![left, right])
+}
+
+fn labels(store: &StoreRef, id: &Id) -> Vec<u32> {
+    let (label, children): Node = id.reify_in(store).unwrap();
+    let mut all = vec![label];
+    for child in &children {
+        all.extend(labels(store, child));
+    }
+    all
+}
+
+fn export(store: &StoreRef, roots: &[Id]) -> Vec<u8> {
+    let mut archive = vec![];
+    archive::export(store, roots, children_of::<Node>, &mut archive).unwrap();
+    archive
+}
+
+#[test]
+fn roundtrip() {
+    let source = StoreRef::memory();
+    let root = tree(&source);
+
+    let mut bytes = vec![];
+    let records =
+        archive::export(&source, &[root], children_of::<Node>, &mut bytes)
+            .unwrap();
+    assert_eq!(records, 5);
+
+    let target = StoreRef::memory();
+    let roots = archive::import(&target, &bytes[..]).unwrap();
+
+    assert_eq!(roots, vec![root]);
+    assert_eq!(labels(&target, &root), vec![0, 2, 1, 3, 1, 4]);
+}
+
+#[test]
+fn from_global_store() {
+    let global = StoreRef::global();
+    let root = tree(&global);
+    let inline = Id::new_inline(&(7u32, Vec::<Id>::new())).unwrap();
+
+    let target = StoreRef::memory();
+    let roots = archive::import(&target, &export(&global, &[inline, root])[..])
+        .unwrap();
+
+    assert_eq!(roots, vec![inline, root]);
+    assert_eq!(labels(&target, &root), labels(&global, &root));
+    assert_eq!(labels(&target, &inline), vec![7]);
+}
+
+#[test]
+fn only_reachable() {
+    let source = StoreRef::memory();
+    let root = tree(&source);
+    let other = node(&source, 5, vec![]);
+
+    let target = StoreRef::memory();
+    archive::import(&target, &export(&source, &[root])[..]).unwrap();
+
+    assert!(matches!(
+        other.reify_in::<Node>(&target),
+        Err(CanonError::NotFound)
+    ));
+}
+
+#[test]
+fn missing_entry() {
+    let source = StoreRef::memory();
+    let missing = Id::new_in(&(9u32, Vec::<Id>::new()), &StoreRef::memory());
+    let root = node(&source, 0, vec![missing]);
+
+    let result = archive::export(&source, &[root], children_of::<Node>, vec![]);
+    assert!(matches!(result, Err(Error::Canon(CanonError::NotFound))));
+}
+
+#[test]
+fn corrupted_record() {
+    let source = StoreRef::memory();
+    let root = tree(&source);
+    let mut bytes = export(&source, &[root]);
+
+    let last = bytes.len() - 1;
+    bytes[last] ^= 1;
+
+    let target = StoreRef::memory();
+    let result = archive::import(&target, &bytes[..]);
+    assert!(matches!(result, Err(Error::Canon(CanonError::Corrupted))));
+}
+
+#[test]
+fn read_records() {
+    let source = StoreRef::memory();
+    let root = tree(&source);
+    let mut bytes = export(&source, &[root]);
+
+    let last = bytes.len() - 1;
+    bytes[last] ^= 1;
+
+    // records are read without being checked
+    let mut reader = archive::Reader::new(&bytes[..]).unwrap();
+    assert_eq!(reader.roots(), [root]);
+    let records: Vec<_> = (&mut reader).map(Result::unwrap).collect();
+    assert_eq!(records.len(), 5);
+    assert_eq!(records[0].0, root);
+    assert!(records[..4]
+        .iter()
+        .all(|(id, bytes)| id.verify(bytes).is_ok()));
+    assert!(records[4].0.verify(&records[4].1).is_err());
+
+    // reading stops at the first error
+    let mut reader = archive::Reader::new(&bytes[..last]).unwrap();
+    assert_eq!(reader.by_ref().filter(Result::is_ok).count(), 4);
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn malformed() {
+    let source = StoreRef::memory();
+    let bytes = export(&source, &[tree(&source)]);
+    let target = StoreRef::memory();
+
+    let mut magic = bytes.clone();
+    magic[0] = b'X';
+    assert!(matches!(
+        archive::import(&target, &magic[..]),
+        Err(Error::InvalidArchive)
+    ));
+
+    let mut version = bytes.clone();
+    version[8] = archive::VERSION + 1;
+    assert!(matches!(
+        archive::import(&target, &version[..]),
+        Err(Error::InvalidArchive)
+    ));
+
+    // an archive cut right after the header has none of its roots
+    let root = archive::Reader::new(&bytes[..]).unwrap().roots()[0];
+    assert!(matches!(
+        archive::import(&target, &bytes[..8 + 1 + 4 + 37]),
+        Err(Error::MissingRoot(id)) if id == root
+    ));
+
+    for len in [4, 20, bytes.len() - 40, bytes.len() - 1] {
+        assert!(matches!(
+            archive::import(&target, &bytes[..len]),
+            Err(Error::Io(_)) | Err(Error::InvalidArchive)
+        ));
+    }
+}
+
+// Version 0 of the bridge ABI can only verify BLAKE2b hashes
+#[cfg(all(
+    feature = "blake3",
+    not(all(feature = "mock-bridge", feature = "legacy-abi"))
+))]
+#[test]
+fn foreign_hash_into_global_store() {
+    use canonical::{
+        Backend, Canon, EncodeToVec, HashAlgorithm, HashConfig, IdVersion,
+        MemoryBackend, Source, Store,
+    };
+
+    // an entry hashed with BLAKE3, while the store uses BLAKE2b
+    let bytes = vec![5u8; 64].encode_to_vec();
+    let hash = HashConfig::new(HashAlgorithm::Blake3).hash(&bytes);
+    let mut encoded =
+        (IdVersion::Blake3.byte(), bytes.len() as u32).encode_to_vec();
+    encoded.extend_from_slice(&hash);
+    let id = Id::decode(&mut Source::new(&encoded)).unwrap();
+
+    let backend = MemoryBackend::new();
+    backend.put(hash, &bytes);
+    let source = StoreRef::new(backend);
+    let mut archive = vec![];
+    archive::export(&source, &[id], |_, _| Ok(vec![]), &mut archive).unwrap();
+
+    assert_eq!(
+        archive::import(&StoreRef::memory(), &archive[..]).unwrap(),
+        [id]
+    );
+    assert_eq!(
+        archive::import(&StoreRef::global(), &archive[..]).unwrap(),
+        [id]
+    );
+
+    // the entry keeps its BLAKE3 hash in the global store
+    assert_eq!(id.reify_checked::<Vec<u8>>().unwrap(), vec![5u8; 64]);
+    let mut buf = vec![0; bytes.len()];
+    assert!(Store::get(&Store::hash(&bytes), &mut buf).is_err());
+}
@@ -0,0 +1,114 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+#![cfg(feature = "async")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use canonical::{
+    AsyncBackend, Backend, CanonError, Id, IdHash, MemoryBackend, StoreRef,
+};
+
+// A backend answering after a delay, like a storage daemon
+#[derive(Default)]
+struct Remote {
+    inner: MemoryBackend,
+    gets: AtomicUsize,
+}
+
+impl AsyncBackend for Remote {
+    async fn get(
+        &self,
+        hash: &IdHash,
+        into: &mut [u8],
+    ) -> Result<(), CanonError> {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        self.gets.fetch_add(1, Ordering::SeqCst);
+        Backend::get(&self.inner, hash, into)
+    }
+
+    async fn put(&self, hash: IdHash, bytes: &[u8]) {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        Backend::put(&self.inner, hash, bytes)
+    }
+}
+
+#[tokio::test]
+async fn memory() {
+    let store = MemoryBackend::new();
+    let id = Id::new_async(&vec![1u8; 64], &store).await;
+
+    assert_eq!(id, Id::new(&vec![1u8; 64]));
+    assert_eq!(id.reify_async::<Vec<u8>, _>(&store).await.unwrap(), [1; 64]);
+}
+
+#[tokio::test]
+async fn shared_with_sync_store() {
+    let backend = Arc::new(MemoryBackend::new());
+    let store = StoreRef::new(backend.clone());
+
+    let id = Id::new_async(&vec![2u8; 64], &backend).await;
+    assert_eq!(id.reify_in::<Vec<u8>>(&store).unwrap(), [2; 64]);
+
+    let id = Id::new_in(&vec![3u8; 64], &store);
+    assert_eq!(
+        id.reify_async::<Vec<u8>, _>(&backend).await.unwrap(),
+        [3; 64]
+    );
+}
+
+#[tokio::test]
+async fn concurrent_gets() {
+    let remote = Arc::new(Remote::default());
+
+    let mut ids = vec![];
+    for n in 0..16u8 {
+        ids.push(Id::new_async(&vec![n; 64], &remote).await);
+    }
+
+    let tasks: Vec<_> = ids
+        .into_iter()
+        .map(|id| {
+            let remote = remote.clone();
+            tokio::spawn(
+                async move { id.reify_async::<Vec<u8>, _>(&remote).await },
+            )
+        })
+        .collect();
+
+    for (n, task) in tasks.into_iter().enumerate() {
+        assert_eq!(task.await.unwrap().unwrap(), vec![n as u8; 64]);
+    }
+    assert_eq!(remote.gets.load(Ordering::SeqCst), 16);
+}
+
+#[tokio::test]
+async fn inline() {
+    let remote = Remote::default();
+    let id = Id::new_inline(&42u64).unwrap();
+
+    assert_eq!(id.reify_async::<u64, _>(&remote).await.unwrap(), 42);
+    assert_eq!(remote.gets.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn errors() {
+    let remote = Remote::default();
+    let missing = Id::new_in(&vec![4u8; 64], &StoreRef::memory());
+
+    assert!(matches!(
+        missing.reify_async::<Vec<u8>, _>(&remote).await,
+        Err(CanonError::NotFound)
+    ));
+
+    remote.put(missing.hash(), &[4; 10]).await;
+    assert!(matches!(
+        missing.reify_async::<Vec<u8>, _>(&remote).await,
+        Err(CanonError::Corrupted)
+    ));
+}
@@ -0,0 +1,62 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+#[cfg(not(all(feature = "mock-bridge", feature = "legacy-abi")))]
+use canonical::CanonError;
+use canonical::{Id, Store};
+
+#[test]
+fn put_and_get_many() {
+    let blobs: Vec<Vec<u8>> =
+        (0..100u8).map(|i| vec![i; 40 + i as usize]).collect();
+    let slices: Vec<&[u8]> = blobs.iter().map(Vec::as_slice).collect();
+
+    let hashes = Store::put_many(&slices);
+    assert_eq!(hashes.len(), blobs.len());
+
+    for (hash, blob) in hashes.iter().zip(&blobs) {
+        assert_eq!(*hash, Store::hash(blob));
+    }
+
+    let mut bufs: Vec<Vec<u8>> =
+        blobs.iter().map(|blob| vec![0; blob.len()]).collect();
+    let mut into: Vec<&mut [u8]> =
+        bufs.iter_mut().map(Vec::as_mut_slice).collect();
+
+    Store::get_many(&hashes, &mut into).unwrap();
+    assert_eq!(bufs, blobs);
+}
+
+#[test]
+#[cfg(not(all(feature = "mock-bridge", feature = "legacy-abi")))]
+fn get_many_errors() {
+    let hashes = Store::put_many(&[&[1; 64], &[2; 64]]);
+    let missing = [0xee; 32];
+
+    let mut a = [0u8; 64];
+    let mut b = [0u8; 64];
+    assert!(matches!(
+        Store::get_many(&[hashes[0], missing], &mut [&mut a, &mut b]),
+        Err(CanonError::NotFound)
+    ));
+
+    let mut short = [0u8; 63];
+    assert!(matches!(
+        Store::get_many(&hashes, &mut [&mut a, &mut short]),
+        Err(CanonError::Corrupted)
+    ));
+}
+
+#[test]
+fn new_many() {
+    let values: Vec<Vec<u32>> = (0..50).map(|i| vec![i; i as usize]).collect();
+    let ids = Id::new_many(&values);
+
+    for (id, value) in ids.iter().zip(&values) {
+        assert_eq!(*id, Id::new(value));
+        assert_eq!(id.reify::<Vec<u32>>().unwrap(), *value);
+    }
+}
@@ -0,0 +1,108 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Checks how the bridge store calls the imports of the mock host
+
+#![cfg(feature = "mock-bridge")]
+
+use canonical::mock::{self, ImportCalls};
+use canonical::{Id, Store};
+
+#[test]
+fn single_values() {
+    mock::reset_calls();
+
+    let id = Id::new(&vec![1u64; 10]);
+    assert_eq!(id.reify::<Vec<u64>>().unwrap(), vec![1; 10]);
+    Store::hash(&[1, 2, 3]);
+
+    assert_eq!(
+        mock::calls(),
+        ImportCalls {
+            put: 1,
+            get: 1,
+            hash: 1,
+            ..ImportCalls::default()
+        }
+    );
+}
+
+#[cfg(not(feature = "legacy-abi"))]
+#[test]
+fn checked_ids_are_hashed_by_the_host() {
+    let id = Id::new(&vec![2u64; 10]);
+    mock::reset_calls();
+
+    assert_eq!(id.reify_checked::<Vec<u64>>().unwrap(), vec![2; 10]);
+
+    assert_eq!(
+        mock::calls(),
+        ImportCalls {
+            get: 1,
+            hash_with: 1,
+            ..ImportCalls::default()
+        }
+    );
+}
+
+#[test]
+fn inline_ids_stay_in_wasm() {
+    mock::reset_calls();
+
+    let id = Id::new_inline(&42u32).unwrap();
+    assert_eq!(id.reify::<u32>().unwrap(), 42);
+
+    assert_eq!(mock::calls(), ImportCalls::default());
+}
+
+#[cfg(not(feature = "legacy-abi"))]
+#[test]
+fn batches_are_single_calls() {
+    mock::reset_calls();
+
+    let values: Vec<Vec<u8>> = (0..100).map(|i| vec![i; 50]).collect();
+    let ids = Id::new_many(&values);
+
+    let hashes: Vec<_> = ids.iter().map(Id::hash).collect();
+    let mut bufs = vec![[0u8; 51]; 100];
+    let mut into: Vec<&mut [u8]> =
+        bufs.iter_mut().map(|b| &mut b[..]).collect();
+    Store::get_many(&hashes, &mut into).unwrap();
+
+    assert_eq!(
+        mock::calls(),
+        ImportCalls {
+            put_many: 1,
+            get_many: 1,
+            ..ImportCalls::default()
+        }
+    );
+}
+
+#[cfg(feature = "legacy-abi")]
+#[test]
+fn batches_without_batched_imports() {
+    mock::reset_calls();
+
+    let values: Vec<Vec<u8>> = (0..100).map(|i| vec![i; 50]).collect();
+    Id::new_many(&values);
+
+    assert_eq!(
+        mock::calls(),
+        ImportCalls {
+            put: 100,
+            ..ImportCalls::default()
+        }
+    );
+}
+
+#[cfg(feature = "legacy-abi")]
+#[test]
+#[should_panic(expected = "host aborted")]
+fn legacy_hosts_abort_on_failed_gets() {
+    let id = Id::raw([0xff; 32], 100);
+    let _ = id.reify::<Vec<u8>>();
+}
@@ -20,7 +20,6 @@ fn test_btree_map() {
     let mut sink = Sink::new(&mut buf);
 
     map.encode(&mut sink);
-    drop(sink);
 
     let mut source = Source::new(&buf);
 
@@ -39,7 +38,6 @@ fn test_btree_set() {
     let mut sink = Sink::new(&mut buf);
 
     set.encode(&mut sink);
-    drop(sink);
 
     let mut source = Source::new(&buf);
 
@@ -0,0 +1,70 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use canonical::{Canon, CanonError, EncodeToVec, Sink, Source};
+use canonical_fuzz::fuzz_canon;
+
+/// A byte encoded through the default, element-wise slice methods
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Byte(u8);
+
+impl Canon for Byte {
+    fn encode(&self, sink: &mut Sink) {
+        self.0.encode(sink)
+    }
+
+    fn decode(source: &mut Source) -> Result<Self, CanonError> {
+        u8::decode(source).map(Byte)
+    }
+
+    fn encoded_len(&self) -> usize {
+        1
+    }
+}
+
+#[test]
+fn bulk_vec_matches_per_element() {
+    let bytes: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+    let wrapped: Vec<Byte> = bytes.iter().copied().map(Byte).collect();
+
+    let encoded = bytes.encode_to_vec();
+    assert_eq!(encoded, wrapped.encode_to_vec());
+    assert_eq!(encoded.len(), bytes.encoded_len());
+
+    let mut source = Source::new(&encoded);
+    assert_eq!(Vec::<u8>::decode(&mut source).unwrap(), bytes);
+
+    let mut source = Source::new(&encoded);
+    assert_eq!(Vec::<Byte>::decode(&mut source).unwrap(), wrapped);
+}
+
+#[test]
+fn bulk_array_matches_per_element() {
+    let mut bytes = [0u8; 77];
+    bytes
+        .iter_mut()
+        .enumerate()
+        .for_each(|(i, b)| *b = i as u8 * 3);
+    let wrapped = bytes.map(Byte);
+
+    let encoded = bytes.encode_to_vec();
+    assert_eq!(encoded, wrapped.encode_to_vec());
+    assert_eq!(&encoded[..], &bytes[..]);
+
+    let mut source = Source::new(&encoded);
+    assert_eq!(<[u8; 77]>::decode(&mut source).unwrap(), bytes);
+
+    let mut source = Source::new(&encoded);
+    assert_eq!(<[Byte; 77]>::decode(&mut source).unwrap(), wrapped);
+}
+
+#[test]
+fn fuzz_bytes() {
+    fuzz_canon::<Vec<u8>>();
+    fuzz_canon::<[u8; 32]>();
+    fuzz_canon::<Vec<[u8; 3]>>();
+    fuzz_canon::<(Vec<u8>, u64)>();
+}
@@ -0,0 +1,209 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+use canonical::{
+    Backend, CacheBackend, CacheStats, CanonError, Id, IdHash, MemoryBackend,
+    Store, StoreRef,
+};
+
+// A backend counting the gets reaching it
+#[derive(Default)]
+struct Slow {
+    inner: MemoryBackend,
+    gets: AtomicUsize,
+}
+
+impl Slow {
+    fn gets(&self) -> usize {
+        self.gets.load(Ordering::SeqCst)
+    }
+}
+
+impl Backend for Slow {
+    fn get(&self, hash: &IdHash, into: &mut [u8]) -> Result<(), CanonError> {
+        self.gets.fetch_add(1, Ordering::SeqCst);
+        self.inner.get(hash, into)
+    }
+
+    fn put(&self, hash: IdHash, bytes: &[u8]) {
+        self.inner.put(hash, bytes)
+    }
+
+    fn remove(&self, hash: &IdHash) -> Option<Vec<u8>> {
+        self.inner.remove(hash)
+    }
+
+    fn entries(&self) -> Vec<(IdHash, usize)> {
+        self.inner.entries()
+    }
+}
+
+fn hash(n: u8) -> IdHash {
+    [n; 32]
+}
+
+fn get<B: Backend>(backend: &B, n: u8, len: usize) -> Result<(), CanonError> {
+    let mut buf = vec![0; len];
+    backend.get(&hash(n), &mut buf)?;
+    assert_eq!(buf, vec![n; len]);
+    Ok(())
+}
+
+#[test]
+fn write_through() {
+    let cache = CacheBackend::new(Slow::default(), 1024);
+    cache.put(hash(1), &[1; 100]);
+
+    assert_eq!(cache.inner().entries(), vec![(hash(1), 100)]);
+
+    get(&cache, 1, 100).unwrap();
+    get(&cache, 1, 100).unwrap();
+    assert_eq!(cache.inner().gets(), 0);
+
+    let stats = cache.cache_stats();
+    assert_eq!((stats.entries, stats.bytes), (1, 100));
+    assert_eq!((stats.hits, stats.misses), (2, 0));
+}
+
+#[test]
+fn read_through() {
+    let slow = Slow::default();
+    slow.put(hash(1), &[1; 100]);
+    let cache = CacheBackend::new(slow, 1024);
+
+    get(&cache, 1, 100).unwrap();
+    get(&cache, 1, 100).unwrap();
+    get(&cache, 1, 100).unwrap();
+    assert_eq!(cache.inner().gets(), 1);
+
+    assert!(matches!(get(&cache, 2, 100), Err(CanonError::NotFound)));
+    assert!(matches!(get(&cache, 1, 99), Err(CanonError::Corrupted)));
+
+    let stats = cache.cache_stats();
+    assert_eq!((stats.hits, stats.misses), (2, 3));
+    assert_eq!(stats.hit_rate(), 0.4);
+    assert_eq!(CacheStats::default().hit_rate(), 0.0);
+}
+
+#[test]
+fn evicts_least_recently_used() {
+    let cache = CacheBackend::new(Slow::default(), 300);
+    for n in 1..=3 {
+        cache.put(hash(n), &[n; 100]);
+    }
+    // 1 becomes the most recently used
+    get(&cache, 1, 100).unwrap();
+
+    cache.put(hash(4), &[4; 100]);
+    assert_eq!(cache.cache_stats().bytes, 300);
+
+    for n in [1, 3, 4] {
+        get(&cache, n, 100).unwrap();
+    }
+    assert_eq!(cache.inner().gets(), 0);
+
+    get(&cache, 2, 100).unwrap();
+    assert_eq!(cache.inner().gets(), 1);
+}
+
+#[test]
+fn capacity() {
+    let cache = CacheBackend::new(Slow::default(), 100);
+    cache.put(hash(1), &[1; 101]);
+
+    assert_eq!(cache.capacity(), 100);
+    assert_eq!(cache.cache_stats().entries, 0);
+    get(&cache, 1, 101).unwrap();
+    assert_eq!(cache.inner().gets(), 1);
+
+    cache.put(hash(2), &[2; 60]);
+    cache.put(hash(2), &[2; 60]);
+    assert_eq!(cache.cache_stats().bytes, 60);
+
+    cache.clear();
+    assert_eq!(cache.cache_stats().bytes, 0);
+}
+
+#[test]
+fn remove() {
+    let cache = CacheBackend::new(Slow::default(), 1024);
+    cache.put(hash(1), &[1; 100]);
+
+    assert_eq!(cache.remove(&hash(1)), Some(vec![1; 100]));
+    assert_eq!(cache.cache_stats().entries, 0);
+    assert!(matches!(get(&cache, 1, 100), Err(CanonError::NotFound)));
+}
+
+// A backend pausing each get after reading its entry, until released
+struct Paused {
+    inner: MemoryBackend,
+    read: Barrier,
+    release: Barrier,
+}
+
+impl Backend for Paused {
+    fn get(&self, hash: &IdHash, into: &mut [u8]) -> Result<(), CanonError> {
+        let result = self.inner.get(hash, into);
+        self.read.wait();
+        self.release.wait();
+        result
+    }
+
+    fn put(&self, hash: IdHash, bytes: &[u8]) {
+        self.inner.put(hash, bytes)
+    }
+
+    fn remove(&self, hash: &IdHash) -> Option<Vec<u8>> {
+        self.inner.remove(hash)
+    }
+}
+
+#[test]
+fn remove_during_get() {
+    let paused = Paused {
+        inner: MemoryBackend::new(),
+        read: Barrier::new(2),
+        release: Barrier::new(2),
+    };
+    paused.put(hash(1), &[1; 100]);
+    let cache = Arc::new(CacheBackend::new(paused, 1024));
+
+    let getter = {
+        let cache = cache.clone();
+        thread::spawn(move || get(&*cache, 1, 100))
+    };
+
+    // the entry is removed after the get read it, but before it is cached
+    cache.inner().read.wait();
+    assert_eq!(cache.remove(&hash(1)), Some(vec![1; 100]));
+    cache.inner().release.wait();
+    getter.join().unwrap().unwrap();
+
+    // the removed entry was not cached by the get
+    assert_eq!(cache.cache_stats().entries, 0);
+    assert_eq!(cache.inner().inner.entries(), vec![]);
+}
+
+#[test]
+fn as_store() {
+    let cache = Arc::new(CacheBackend::new(MemoryBackend::new(), 1 << 20));
+    let store = StoreRef::new(cache.clone());
+
+    let ids = Store::scoped(store.clone(), || {
+        Id::new_many(&[vec![1u8; 50], vec![2u8; 50]])
+    });
+    for id in ids.iter().chain(&ids) {
+        id.reify_in::<Vec<u8>>(&store).unwrap();
+    }
+
+    assert_eq!(cache.cache_stats().hits, 4);
+    assert_eq!(store.stats().entries, 2);
+    assert_eq!(store.stats().hits, 4);
+}
@@ -0,0 +1,89 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use core::cmp::{Ordering, Reverse};
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroU128, NonZeroU16,
+    NonZeroU32, NonZeroU64, NonZeroU8, Wrapping,
+};
+use core::ops::{Range, RangeInclusive};
+use core::time::Duration;
+
+use canonical::{Canon, CanonError, EncodeToVec, Source};
+use canonical_fuzz::fuzz_canon;
+
+fn decode<T: Canon>(bytes: &[u8]) -> Result<T, CanonError> {
+    let mut source = Source::new(bytes);
+    T::decode(&mut source)
+}
+
+#[test]
+fn fuzz_nonzero() {
+    fuzz_canon::<NonZeroU8>();
+    fuzz_canon::<NonZeroU16>();
+    fuzz_canon::<NonZeroI16>();
+    fuzz_canon::<NonZeroU32>();
+    fuzz_canon::<NonZeroI32>();
+    fuzz_canon::<NonZeroU64>();
+    fuzz_canon::<NonZeroI64>();
+    fuzz_canon::<NonZeroU128>();
+    fuzz_canon::<NonZeroI128>();
+}
+
+#[test]
+fn nonzero_rejects_zero() {
+    let zero = 0u64.encode_to_vec();
+
+    assert!(decode::<NonZeroU64>(&zero).is_err());
+    assert!(decode::<NonZeroI64>(&zero).is_err());
+    assert!(decode::<NonZeroU8>(&[0]).is_err());
+    assert!(decode::<NonZeroU128>(&0u128.encode_to_vec()).is_err());
+}
+
+#[test]
+fn nonzero_same_encoding_as_int() {
+    let n = NonZeroU64::new(1337).unwrap();
+    assert_eq!(n.encode_to_vec(), 1337u64.encode_to_vec());
+}
+
+#[test]
+fn fuzz_wrapping_reverse() {
+    fuzz_canon::<Wrapping<u32>>();
+    fuzz_canon::<Reverse<u64>>();
+    fuzz_canon::<Reverse<(u8, String)>>();
+}
+
+#[test]
+fn ordering() {
+    for ord in [Ordering::Less, Ordering::Equal, Ordering::Greater] {
+        assert_eq!(decode::<Ordering>(&ord.encode_to_vec()).unwrap(), ord);
+    }
+
+    assert!(decode::<Ordering>(&[3]).is_err());
+}
+
+#[test]
+fn fuzz_duration() {
+    fuzz_canon::<Duration>();
+}
+
+#[test]
+fn duration_rejects_excess_nanos() {
+    let valid = (7u64, 999_999_999u32).encode_to_vec();
+    assert_eq!(
+        decode::<Duration>(&valid).unwrap(),
+        Duration::new(7, 999_999_999)
+    );
+
+    let invalid = (7u64, 1_000_000_000u32).encode_to_vec();
+    assert!(decode::<Duration>(&invalid).is_err());
+}
+
+#[test]
+fn fuzz_ranges() {
+    fuzz_canon::<Range<u64>>();
+    fuzz_canon::<RangeInclusive<i32>>();
+}
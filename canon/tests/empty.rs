@@ -0,0 +1,47 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Zero-length values, which the bridge passes to the host as empty slices.
+//! Run with the `mock-bridge` feature to exercise the bridge store.
+
+use canonical::{EncodeToVec, HashConfig, Id, Store};
+
+#[test]
+fn empty_values() {
+    assert_eq!(().encode_to_vec(), Vec::<u8>::new());
+
+    let id = Id::new(&());
+    assert_eq!(id.size(), 0);
+    id.reify::<()>().unwrap();
+    id.reify_checked::<()>().unwrap();
+    assert_eq!(id.take_bytes().unwrap(), Some(vec![]));
+
+    let id = Id::new(&[0u8; 0]);
+    assert_eq!(id.reify::<[u8; 0]>().unwrap(), []);
+}
+
+#[test]
+fn empty_store_access() {
+    let hash = Store::put(&[]);
+    assert_eq!(hash, Store::hash(&[]));
+    assert_eq!(hash, HashConfig::DEFAULT.hash(&[]));
+
+    Store::get(&hash, &mut []).unwrap();
+}
+
+#[test]
+fn empty_batches() {
+    assert!(Store::put_many(&[]).is_empty());
+    Store::get_many(&[], &mut []).unwrap();
+
+    let hashes = Store::put_many(&[&[], &[1; 40], &[]]);
+    assert_eq!(hashes[0], Store::hash(&[]));
+    assert_eq!(hashes[0], hashes[2]);
+
+    let mut buf = [0u8; 40];
+    Store::get_many(&hashes, &mut [&mut [], &mut buf, &mut []]).unwrap();
+    assert_eq!(buf, [1; 40]);
+}
@@ -0,0 +1,158 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! The external types are wrapped to provide `Arbitrary` for fuzzing
+
+#![allow(unused_imports)]
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use canonical::{Canon, EncodeToVec, Source};
+use canonical_derive::Canon;
+use canonical_fuzz::fuzz_canon;
+
+#[cfg(feature = "bytes")]
+mod bytes_tests {
+    use super::*;
+    use bytes::Bytes;
+
+    #[derive(Clone, Canon, PartialEq, Debug)]
+    struct Wrapper(Bytes);
+
+    impl<'a> Arbitrary<'a> for Wrapper {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            Ok(Wrapper(Vec::<u8>::arbitrary(u)?.into()))
+        }
+    }
+
+    #[test]
+    fn fuzz_bytes() {
+        fuzz_canon::<Wrapper>();
+    }
+
+    #[test]
+    fn same_as_vec() {
+        let vec = vec![1u8, 2, 3, 255];
+        assert_eq!(
+            Bytes::from(vec.clone()).encode_to_vec(),
+            vec.encode_to_vec()
+        );
+    }
+}
+
+#[cfg(feature = "smallvec")]
+mod smallvec_tests {
+    use super::*;
+    use smallvec::SmallVec;
+
+    #[derive(Clone, Canon, PartialEq, Debug)]
+    struct Wrapper(SmallVec<[u32; 4]>, SmallVec<[u8; 8]>);
+
+    impl<'a> Arbitrary<'a> for Wrapper {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            Ok(Wrapper(
+                Vec::<u32>::arbitrary(u)?.into(),
+                Vec::<u8>::arbitrary(u)?.into(),
+            ))
+        }
+    }
+
+    #[test]
+    fn fuzz_smallvec() {
+        fuzz_canon::<Wrapper>();
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+mod arrayvec_tests {
+    use super::*;
+    use arrayvec::ArrayVec;
+
+    #[derive(Clone, Canon, PartialEq, Debug)]
+    struct Wrapper(ArrayVec<u64, 8>);
+
+    impl<'a> Arbitrary<'a> for Wrapper {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            let mut vec = ArrayVec::new();
+            for _ in 0..u.int_in_range(0..=8)? {
+                vec.push(u64::arbitrary(u)?);
+            }
+            Ok(Wrapper(vec))
+        }
+    }
+
+    #[test]
+    fn fuzz_arrayvec() {
+        fuzz_canon::<Wrapper>();
+    }
+
+    #[test]
+    fn over_capacity() {
+        let bytes = vec![1u64, 2, 3].encode_to_vec();
+        let mut source = Source::new(&bytes);
+        assert!(ArrayVec::<u64, 2>::decode(&mut source).is_err());
+    }
+}
+
+#[cfg(feature = "heapless")]
+mod heapless_tests {
+    use super::*;
+    use heapless::Vec as HeaplessVec;
+
+    #[derive(Clone, Canon, PartialEq, Debug)]
+    struct Wrapper(HeaplessVec<i32, 8>);
+
+    impl<'a> Arbitrary<'a> for Wrapper {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            let mut vec = HeaplessVec::new();
+            for _ in 0..u.int_in_range(0..=8)? {
+                vec.push(i32::arbitrary(u)?).unwrap();
+            }
+            Ok(Wrapper(vec))
+        }
+    }
+
+    #[test]
+    fn fuzz_heapless() {
+        fuzz_canon::<Wrapper>();
+    }
+
+    #[test]
+    fn over_capacity() {
+        let bytes = vec![1i32, 2, 3].encode_to_vec();
+        let mut source = Source::new(&bytes);
+        assert!(HeaplessVec::<i32, 2>::decode(&mut source).is_err());
+    }
+}
+
+#[cfg(feature = "hashbrown")]
+mod hashbrown_tests {
+    use super::*;
+    use hashbrown::HashMap;
+    use std::collections::BTreeMap;
+
+    #[derive(Clone, Canon, PartialEq, Debug)]
+    struct Wrapper(HashMap<u32, String>);
+
+    impl<'a> Arbitrary<'a> for Wrapper {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            let map = BTreeMap::<u32, String>::arbitrary(u)?;
+            Ok(Wrapper(map.into_iter().collect()))
+        }
+    }
+
+    #[test]
+    fn fuzz_hashbrown() {
+        fuzz_canon::<Wrapper>();
+    }
+
+    #[test]
+    fn same_as_btree_map() {
+        let btree: BTreeMap<u64, u8> = (0..500).map(|i| (i * 7, 1)).collect();
+        let hash: HashMap<u64, u8> = btree.clone().into_iter().collect();
+
+        assert_eq!(hash.encode_to_vec(), btree.encode_to_vec());
+    }
+}
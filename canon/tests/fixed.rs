@@ -0,0 +1,58 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use canonical::{Canon, EncodeToVec, FixedBE, FixedLE, Source};
+use canonical_fuzz::fuzz_canon;
+
+#[test]
+fn full_width() {
+    assert_eq!(FixedLE(1u64).encode_to_vec(), [1, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(FixedBE(1u64).encode_to_vec(), [0, 0, 0, 0, 0, 0, 0, 1]);
+    assert_eq!(FixedLE(-2i16).encode_to_vec(), [0xfe, 0xff]);
+    assert_eq!(FixedBE(u32::MAX).encode_to_vec(), [0xff; 4]);
+    assert_eq!(FixedLE(0u128).encoded_len(), 16);
+}
+
+#[test]
+fn fixed_offsets() {
+    let records = vec![FixedBE(3u32), FixedBE(1_000_000), FixedBE(7)];
+    let mut encoded = records.encode_to_vec();
+
+    // patch the second record in place, after the one byte length prefix
+    encoded[5..9].copy_from_slice(&FixedBE(42u32).encode_to_vec());
+
+    let mut source = Source::new(&encoded);
+    let decoded = Vec::<FixedBE<u32>>::decode(&mut source).unwrap();
+    assert_eq!(decoded, [FixedBE(3), FixedBE(42), FixedBE(7)]);
+}
+
+#[test]
+fn big_endian_sorts_bytewise() {
+    let mut values = [7u64, 0, 1 << 40, 255, 256, u64::MAX];
+    let mut encoded: Vec<_> =
+        values.iter().map(|v| FixedBE(*v).encode_to_vec()).collect();
+
+    values.sort_unstable();
+    encoded.sort();
+
+    for (value, bytes) in values.iter().zip(encoded) {
+        assert_eq!(bytes, FixedBE(*value).encode_to_vec());
+    }
+}
+
+#[test]
+fn fuzz_fixed() {
+    fuzz_canon::<FixedLE<u8>>();
+    fuzz_canon::<FixedBE<i8>>();
+    fuzz_canon::<FixedLE<u16>>();
+    fuzz_canon::<FixedBE<i16>>();
+    fuzz_canon::<FixedLE<i32>>();
+    fuzz_canon::<FixedBE<u32>>();
+    fuzz_canon::<FixedLE<u64>>();
+    fuzz_canon::<FixedBE<i64>>();
+    fuzz_canon::<FixedLE<i128>>();
+    fuzz_canon::<FixedBE<u128>>();
+}
@@ -0,0 +1,124 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use canonical::{HashAlgorithm, HashConfig, IdHash, Store};
+
+fn hex(hash: IdHash) -> String {
+    hash.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// (domain, input, expected hash)
+type Vector = (&'static [u8], &'static [u8], &'static str);
+
+fn check_vectors(algorithm: HashAlgorithm, vectors: &[Vector]) {
+    for (domain, input, expected) in vectors {
+        let config = HashConfig::new(algorithm).with_domain(1, domain);
+        assert_eq!(hex(config.hash(input)), *expected);
+    }
+}
+
+fn check_domain_prefix(algorithm: HashAlgorithm) {
+    let domain = b"some domain";
+    let input = b"some input";
+
+    let mut prefixed = (domain.len() as u32).to_le_bytes().to_vec();
+    prefixed.extend_from_slice(domain);
+    prefixed.extend_from_slice(input);
+
+    let plain = HashConfig::new(algorithm);
+    let tagged = plain.with_domain(1, domain);
+
+    assert_eq!(tagged.hash(input), plain.hash(&prefixed));
+    assert_ne!(tagged.hash(input), plain.hash(input));
+}
+
+#[test]
+fn blake2b_vectors() {
+    check_vectors(
+        HashAlgorithm::Blake2b,
+        &[
+            (
+                b"",
+                b"",
+                "0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8",
+            ),
+            (
+                b"",
+                b"abc",
+                "bddd813c634239723171ef3fee98579b94964e3bb1cb3e427262c8c068d52319",
+            ),
+            (
+                b"canon",
+                b"",
+                "259112378d8c357e31f6173acf20b3c900a9f908c087fad8ca0933ef6dd2ba35",
+            ),
+            (
+                b"canon",
+                b"abc",
+                "93725452b958bf0cdf8bf908319e397416256b4a193c79351b5df5d13a86237e",
+            ),
+        ],
+    );
+    check_domain_prefix(HashAlgorithm::Blake2b);
+}
+
+#[cfg(feature = "blake3")]
+#[test]
+fn blake3_vectors() {
+    check_vectors(
+        HashAlgorithm::Blake3,
+        &[
+            (
+                b"",
+                b"",
+                "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262",
+            ),
+            (
+                b"",
+                b"abc",
+                "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85",
+            ),
+        ],
+    );
+    check_domain_prefix(HashAlgorithm::Blake3);
+}
+
+#[cfg(feature = "sha2")]
+#[test]
+fn sha256_vectors() {
+    check_vectors(
+        HashAlgorithm::Sha256,
+        &[
+            (
+                b"",
+                b"",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                b"",
+                b"abc",
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                b"canon",
+                b"",
+                "a8129d0c50def98b9c685c94c53c6e0f1da9f4e698859c6734939639ef82d38b",
+            ),
+            (
+                b"canon",
+                b"abc",
+                "fbf39620095a6312d085c43d716c55b7e6c0f159609274fe90fcbcd21d402a6c",
+            ),
+        ],
+    );
+    check_domain_prefix(HashAlgorithm::Sha256);
+}
+
+#[test]
+fn store_uses_default_config() {
+    assert_eq!(Store::hash_config(), HashConfig::default());
+    assert_eq!(Store::hash(b"abc"), HashConfig::DEFAULT.hash(b"abc"));
+}
@@ -0,0 +1,135 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Changing the hash configuration affects the whole process, so this is kept
+//! in its own test binary with a single test.
+//!
+//! With the `mock-bridge` feature, the store hashes through the imports of
+//! the mock host. Modules of version 0 of the ABI only run under the default
+//! configuration, so this does not apply to them.
+
+#![cfg(not(all(feature = "mock-bridge", feature = "legacy-abi")))]
+
+use std::panic;
+
+use canonical::{
+    Canon, CanonError, EncodeToVec, HashAlgorithm, HashConfig, Id, IdHash,
+    IdVersion, Source, Store,
+};
+
+fn hex(hash: IdHash) -> String {
+    hash.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// (configuration, expected hash of "abc")
+const VECTORS: &[(HashConfig, &str)] = &[
+    (
+        HashConfig::DEFAULT,
+        "bddd813c634239723171ef3fee98579b94964e3bb1cb3e427262c8c068d52319",
+    ),
+    (
+        HashConfig::new(HashAlgorithm::Blake2b).with_domain(1, b"canon"),
+        "93725452b958bf0cdf8bf908319e397416256b4a193c79351b5df5d13a86237e",
+    ),
+    #[cfg(feature = "blake3")]
+    (
+        HashConfig::new(HashAlgorithm::Blake3),
+        "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85",
+    ),
+    #[cfg(feature = "sha2")]
+    (
+        HashConfig::new(HashAlgorithm::Sha256),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+    ),
+    #[cfg(feature = "sha2")]
+    (
+        HashConfig::new(HashAlgorithm::Sha256).with_domain(2, b"canon"),
+        "fbf39620095a6312d085c43d716c55b7e6c0f159609274fe90fcbcd21d402a6c",
+    ),
+];
+
+fn store_vectors() {
+    for (config, expected) in VECTORS {
+        Store::set_hash_config(*config);
+        assert_eq!(hex(Store::hash(b"abc")), *expected);
+        assert_eq!(hex(Store::put(b"abc")), *expected);
+
+        let id = Id::new(&vec![7u8; 64]);
+        assert_eq!(id.version(), config.id_version());
+        assert_eq!(id.hash(), config.hash(&vec![7u8; 64].encode_to_vec()));
+        assert_eq!(id.reify_checked::<Vec<u8>>().unwrap(), vec![7u8; 64]);
+    }
+    Store::set_hash_config(HashConfig::DEFAULT);
+}
+
+#[test]
+fn configured_hash() {
+    const CONFIG: HashConfig =
+        HashConfig::new(HashAlgorithm::Blake2b).with_domain(10, b"test");
+    const OTHER: HashConfig =
+        HashConfig::new(HashAlgorithm::Blake2b).with_domain(11, b"other");
+
+    let value = String::from("a value long enough to not be inlined");
+    let bytes = value.encode_to_vec();
+
+    let default_id = Id::new(&value);
+    assert_eq!(default_id.hash(), HashConfig::DEFAULT.hash(&bytes));
+
+    Store::set_hash_config(CONFIG);
+    assert_eq!(Store::hash_config(), CONFIG);
+
+    let id = Id::new(&value);
+    assert_eq!(id.version(), IdVersion::Domain(10));
+    assert_eq!(id.hash(), CONFIG.hash(&bytes));
+    assert_ne!(id, default_id);
+    assert_eq!(id.reify::<String>().unwrap(), value);
+    assert_eq!(id.reify_checked::<String>().unwrap(), value);
+
+    // values stored under another configuration still verify
+    assert_eq!(default_id.reify_checked::<String>().unwrap(), value);
+    assert!(!default_id.is_current());
+
+    Store::set_hash_config(OTHER);
+    let other_id = Id::new(&value);
+    assert_eq!(other_id.version(), IdVersion::Domain(11));
+    assert_eq!(other_id.reify_checked::<String>().unwrap(), value);
+    assert_eq!(id.reify_checked::<String>().unwrap(), value);
+
+    // and can be migrated
+    assert_eq!(default_id.rehash().unwrap(), other_id);
+    assert_eq!(id.rehash().unwrap(), other_id);
+
+    // domains never set can not be verified
+    let mut encoded = id.encode_to_vec();
+    encoded[0] = IdVersion::Domain(12).byte();
+    let unknown = Id::decode(&mut Source::new(&encoded)).unwrap();
+    assert!(matches!(
+        unknown.reify_checked::<String>(),
+        Err(CanonError::InvalidEncoding)
+    ));
+
+    // modules of version 0 of the ABI can not follow the configuration
+    #[cfg(feature = "imports")]
+    {
+        use canonical::imports;
+
+        assert!(imports::check_version(0).is_err());
+        assert!(imports::check_version(1).is_ok());
+    }
+
+    // domain ids are not reused for another domain
+    let reused = HashConfig::new(HashAlgorithm::Blake2b).with_domain(10, b"x");
+    assert!(panic::catch_unwind(|| Store::set_hash_config(reused)).is_err());
+    assert_eq!(Store::hash_config(), OTHER);
+
+    // nor out of the range of the version byte
+    let mut invalid = CONFIG;
+    invalid.domain_id = 0x80 | 10;
+    assert!(panic::catch_unwind(|| Store::set_hash_config(invalid)).is_err());
+    assert_eq!(Store::hash_config(), OTHER);
+
+    store_vectors();
+}
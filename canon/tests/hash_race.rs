@@ -0,0 +1,48 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Changing the hash configuration affects the whole process, so this is kept
+//! in its own test binary with a single test.
+//!
+//! Modules of version 0 of the ABI only run under the default configuration.
+
+#![cfg(not(all(feature = "mock-bridge", feature = "legacy-abi")))]
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use canonical::{HashConfig, Id, Store};
+
+#[test]
+fn ids_made_during_config_changes() {
+    let domain = HashConfig::DEFAULT.with_domain(20, b"race");
+    let done = Arc::new(AtomicBool::new(false));
+
+    let changes = {
+        let done = done.clone();
+        thread::spawn(move || {
+            while !done.load(Ordering::Relaxed) {
+                Store::set_hash_config(domain);
+                Store::set_hash_config(HashConfig::DEFAULT);
+            }
+        })
+    };
+
+    // the version of every id records the configuration of its hash
+    for i in 0..2000u32 {
+        let id = Id::new(&[i; 16]);
+        assert_eq!(id.reify_checked::<[u32; 16]>().unwrap(), [i; 16]);
+
+        for id in Id::new_many(&[[i; 16], [!i; 16]]) {
+            assert!(id.reify_checked::<[u32; 16]>().is_ok());
+        }
+    }
+
+    done.store(true, Ordering::Relaxed);
+    changes.join().unwrap();
+    Store::set_hash_config(HashConfig::DEFAULT);
+}
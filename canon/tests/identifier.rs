@@ -4,7 +4,11 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
-use canonical::{Canon, Id, Sink, Source};
+use canonical::{
+    Canon, CanonError, EncodeToVec, HashConfig, Id, IdVersion, Sink, Source,
+    TypedId,
+};
+use canonical_derive::Canon;
 use canonical_fuzz::fuzz_canon;
 
 #[test]
@@ -28,3 +32,215 @@ fn test_empty() {
 fn fuzz_id() {
     fuzz_canon::<Id>()
 }
+
+#[test]
+fn fuzz_typed_id() {
+    fuzz_canon::<TypedId<Vec<u32>>>()
+}
+
+#[test]
+fn typed_id_reify() {
+    let value = String::from("a value long enough to not be inlined at all");
+    let typed = TypedId::new(&value);
+
+    assert_eq!(typed.reify().unwrap(), value);
+    assert_eq!(typed.id(), Id::new(&value));
+    assert_eq!(Id::from(typed).reify::<String>().unwrap(), value);
+}
+
+#[test]
+fn typed_id_encoding() {
+    let id = Id::new(&vec![1u64, 2, 3]);
+    let typed = TypedId::<Vec<u64>>::from(id);
+
+    assert_eq!(typed.encode_to_vec(), id.encode_to_vec());
+    assert_eq!(typed.encoded_len(), id.encoded_len());
+
+    let bytes = id.encode_to_vec();
+    let decoded = TypedId::<Vec<u64>>::decode(&mut Source::new(&bytes));
+    assert_eq!(decoded.unwrap(), typed);
+}
+
+#[derive(Clone, Canon, Debug, PartialEq)]
+struct Node {
+    value: u64,
+    children: Vec<TypedId<Node>>,
+}
+
+#[test]
+fn typed_id_in_derived() {
+    let leaf = Node {
+        value: 1,
+        children: vec![],
+    };
+    let root = Node {
+        value: 2,
+        children: vec![TypedId::new(&leaf), TypedId::new(&leaf)],
+    };
+
+    let restored = TypedId::new(&root).reify().unwrap();
+    assert_eq!(restored, root);
+    assert_eq!(restored.children[1].reify().unwrap(), leaf);
+}
+
+#[test]
+#[cfg(not(all(feature = "mock-bridge", feature = "legacy-abi")))]
+fn reify_wrong_length() {
+    let value = vec![7u8; 100];
+    let id = Id::new(&value);
+
+    assert_eq!(id.reify_checked::<Vec<u8>>().unwrap(), value);
+
+    for len in [0, id.size() - 1, id.size() + 1] {
+        let wrong = Id::raw(id.hash(), len as u32);
+        assert!(matches!(
+            wrong.reify::<Vec<u8>>(),
+            Err(CanonError::Corrupted)
+        ));
+        assert!(matches!(
+            wrong.reify_checked::<Vec<u8>>(),
+            Err(CanonError::Corrupted)
+        ));
+    }
+
+    let mut buf = vec![0u8; id.size() + 1];
+    assert!(matches!(
+        canonical::Store::get(&id.hash(), &mut buf),
+        Err(CanonError::Corrupted)
+    ));
+
+    // a corrupted take leaves the entry in the store
+    let wrong = Id::raw(id.hash(), id.size() as u32 + 1);
+    assert!(matches!(wrong.take_bytes(), Err(CanonError::Corrupted)));
+    assert_eq!(id.reify::<Vec<u8>>().unwrap(), value);
+}
+
+#[test]
+#[cfg(not(all(feature = "mock-bridge", feature = "legacy-abi")))]
+fn reify_missing() {
+    let id = Id::raw([0xff; 32], 100);
+    assert!(matches!(id.reify::<Vec<u8>>(), Err(CanonError::NotFound)));
+}
+
+#[test]
+fn id_string_round_trip() {
+    let id = Id::raw([0xab; 32], 300);
+    let string = id.to_string();
+
+    assert_eq!(
+        string,
+        format!("00{:08x}{}{}", 300, "ab".repeat(32), &string[74..])
+    );
+    assert_eq!(string.parse::<Id>().unwrap(), id);
+    assert_eq!(string.to_uppercase().parse::<Id>().unwrap(), id);
+
+    let typed = TypedId::<Vec<u8>>::new(&vec![1; 64]);
+    assert_eq!(typed.to_string(), typed.id().to_string());
+    assert_eq!(
+        typed.to_string().parse::<TypedId<Vec<u8>>>().unwrap(),
+        typed
+    );
+}
+
+// Replaces the version of an id string with 4, with a matching checksum
+fn unknown_version(string: &str) -> String {
+    assert!(IdVersion::from_byte(4).is_none());
+
+    let mut bytes: Vec<u8> = (0..string.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&string[i..i + 2], 16).unwrap())
+        .collect();
+    bytes[0] = 4;
+    let checked = bytes.len() - 4;
+    let checksum = HashConfig::DEFAULT.hash(&bytes[..checked]);
+    bytes[checked..].copy_from_slice(&checksum[..4]);
+
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[test]
+fn id_string_rejects_invalid() {
+    let string = Id::raw([0xab; 32], 300).to_string();
+
+    let invalid = [
+        String::new(),
+        string[1..].to_string(),
+        format!("{}0", string),
+        // flipped digit in the hash, failing the checksum
+        string.replacen("ab", "ac", 1),
+        // sign characters are not hex digits
+        format!("+{}", &string[1..]),
+        // no version is assigned the byte 4, the checksum being valid
+        unknown_version(&string),
+    ];
+
+    for s in &invalid {
+        assert!(
+            matches!(s.parse::<Id>(), Err(CanonError::InvalidEncoding)),
+            "{} should not parse",
+            s
+        );
+    }
+}
+
+#[test]
+fn inline_id() {
+    let value = (42u64, String::from("short"));
+    let id = Id::new_inline(&value).unwrap();
+
+    assert_eq!(id.version(), IdVersion::Inline);
+    assert_eq!(id.inline_bytes().unwrap(), &value.encode_to_vec()[..]);
+    assert_eq!(id.reify::<(u64, String)>().unwrap(), value);
+    assert_eq!(id.reify_checked::<(u64, String)>().unwrap(), value);
+    assert!(id.take_bytes().unwrap().is_none());
+
+    // the version, the length and the inlined bytes
+    let encoded = id.encode_to_vec();
+    assert_eq!(encoded.len(), 1 + 1 + id.size());
+    assert_eq!(encoded.len(), id.encoded_len());
+    assert_eq!(encoded[0], 1);
+    assert_eq!(Id::decode(&mut Source::new(&encoded)).unwrap(), id);
+
+    assert_eq!(id.to_string().parse::<Id>().unwrap(), id);
+    assert!(Id::new_inline(&[0u8; 33]).is_none());
+    assert!(Id::new_inline(&[0u8; 32]).is_some());
+}
+
+#[test]
+fn id_versions() {
+    for byte in 0..=255 {
+        if let Some(version) = IdVersion::from_byte(byte) {
+            assert_eq!(version.byte(), byte);
+        }
+    }
+    assert_eq!(IdVersion::from_byte(4), None);
+    assert_eq!(IdVersion::from_byte(0x85), Some(IdVersion::Domain(5)));
+    assert_eq!(IdVersion::Inline.algorithm(), None);
+    assert_eq!(IdVersion::Domain(5).algorithm(), None);
+
+    let id = Id::new(&vec![1u8; 100]);
+    assert_eq!(id.version(), IdVersion::Blake2b);
+    assert!(id.is_current());
+
+    let mut bytes = id.encode_to_vec();
+
+    // unknown version
+    bytes[0] = 4;
+    assert!(Id::decode(&mut Source::new(&bytes)).is_err());
+
+    // inline ids hold at most 32 bytes
+    bytes[0] = IdVersion::Inline.byte();
+    assert!(Id::decode(&mut Source::new(&bytes)).is_err());
+}
+
+#[test]
+fn rehash_inline() {
+    let value = vec![1u16, 2, 3];
+    let inline = Id::new_inline(&value).unwrap();
+    assert!(!inline.is_current());
+
+    let hashed = inline.rehash().unwrap();
+    assert_eq!(hashed, Id::new(&value));
+    assert_eq!(hashed.hash(), inline.hash());
+    assert_eq!(hashed.reify::<Vec<u16>>().unwrap(), value);
+}
@@ -0,0 +1,354 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Runs handwritten wasm modules against the host-side imports, registered
+//! with the `wasmi` engine.
+
+#![cfg(feature = "imports")]
+
+use canonical::imports::{self, Trap};
+use canonical::{abi, IdVersion, Store, StoreRef};
+use wasmi::{Caller, Engine, Extern, Instance, Linker, Module};
+
+type WasmStore = wasmi::Store<()>;
+
+// A module built against the latest ABI, exporting the imports for the test
+// to call them from within wasm
+const MODULE: &str = r#"
+(module
+  (import "canon" "put" (func $put (param i32 i32 i32) (result i32)))
+  (import "canon" "get" (func $get (param i32 i32 i32) (result i32)))
+  (import "canon" "hash" (func $hash (param i32 i32 i32) (result i32)))
+  (import "canon" "hash_version" (func $hash_version (result i32)))
+  (import "canon" "hash_with"
+    (func $hash_with (param i32 i32 i32 i32) (result i32)))
+  (import "canon" "put_many"
+    (func $put_many (param i32 i32 i32 i32) (result i32)))
+  (import "canon" "get_many"
+    (func $get_many (param i32 i32 i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (func (export "canon_abi_version") (result i32) i32.const 1)
+  (func (export "call_put") (param i32 i32 i32) (result i32)
+    local.get 0 local.get 1 local.get 2 call $put)
+  (func (export "call_get") (param i32 i32 i32) (result i32)
+    local.get 0 local.get 1 local.get 2 call $get)
+  (func (export "call_hash") (param i32 i32 i32) (result i32)
+    local.get 0 local.get 1 local.get 2 call $hash)
+  (func (export "call_hash_version") (result i32) call $hash_version)
+  (func (export "call_hash_with") (param i32 i32 i32 i32) (result i32)
+    local.get 0 local.get 1 local.get 2 local.get 3 call $hash_with)
+  (func (export "call_put_many") (param i32 i32 i32 i32) (result i32)
+    local.get 0 local.get 1 local.get 2 local.get 3 call $put_many)
+  (func (export "call_get_many") (param i32 i32 i32 i32) (result i32)
+    local.get 0 local.get 1 local.get 2 local.get 3 call $get_many))
+"#;
+
+// A module built against version 0, without a version export
+const LEGACY_MODULE: &str = r#"
+(module
+  (import "canon" "put" (func $put (param i32 i32 i32)))
+  (import "canon" "get" (func $get (param i32 i32 i32)))
+  (import "canon" "hash" (func $hash (param i32 i32 i32)))
+  (memory (export "memory") 1)
+  (func (export "call_put") (param i32 i32 i32)
+    local.get 0 local.get 1 local.get 2 call $put)
+  (func (export "call_get") (param i32 i32 i32)
+    local.get 0 local.get 1 local.get 2 call $get)
+  (func (export "call_hash") (param i32 i32 i32)
+    local.get 0 local.get 1 local.get 2 call $hash))
+"#;
+
+fn memory(caller: &mut Caller<'_, ()>) -> wasmi::Memory {
+    caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .expect("the module exports its memory")
+}
+
+fn trap(trap: Trap) -> wasmi::Error {
+    wasmi::Error::new(trap.to_string())
+}
+
+// Registers the imports matching the ABI version of the module
+fn linker(engine: &Engine, version: i32) -> Linker<()> {
+    let mut linker = Linker::new(engine);
+
+    if version == 0 {
+        linker
+            .func_wrap("canon", "put", |mut c: Caller<'_, ()>, a, b, r| {
+                let mem = memory(&mut c);
+                imports::put_legacy(mem.data_mut(&mut c), a, b, r).map_err(trap)
+            })
+            .unwrap();
+        linker
+            .func_wrap("canon", "get", |mut c: Caller<'_, ()>, h, b, l| {
+                let mem = memory(&mut c);
+                imports::get_legacy(mem.data_mut(&mut c), h, b, l).map_err(trap)
+            })
+            .unwrap();
+        linker
+            .func_wrap("canon", "hash", |mut c: Caller<'_, ()>, a, b, r| {
+                let mem = memory(&mut c);
+                imports::hash_legacy(mem.data_mut(&mut c), a, b, r)
+                    .map_err(trap)
+            })
+            .unwrap();
+        return linker;
+    }
+
+    linker
+        .func_wrap("canon", "put", |mut c: Caller<'_, ()>, a, b, r| {
+            let mem = memory(&mut c);
+            imports::put(mem.data_mut(&mut c), a, b, r).map_err(trap)
+        })
+        .unwrap();
+    linker
+        .func_wrap("canon", "get", |mut c: Caller<'_, ()>, h, b, l| {
+            let mem = memory(&mut c);
+            imports::get(mem.data_mut(&mut c), h, b, l).map_err(trap)
+        })
+        .unwrap();
+    linker
+        .func_wrap("canon", "hash", |mut c: Caller<'_, ()>, a, b, r| {
+            let mem = memory(&mut c);
+            imports::hash(mem.data_mut(&mut c), a, b, r).map_err(trap)
+        })
+        .unwrap();
+    linker
+        .func_wrap("canon", "hash_version", imports::hash_version)
+        .unwrap();
+    linker
+        .func_wrap("canon", "hash_with", |mut c: Caller<'_, ()>, v, b, l, r| {
+            let mem = memory(&mut c);
+            imports::hash_with(mem.data_mut(&mut c), v, b, l, r).map_err(trap)
+        })
+        .unwrap();
+    linker
+        .func_wrap("canon", "put_many", |mut c: Caller<'_, ()>, b, l, n, r| {
+            let mem = memory(&mut c);
+            imports::put_many(mem.data_mut(&mut c), b, l, n, r).map_err(trap)
+        })
+        .unwrap();
+    linker
+        .func_wrap("canon", "get_many", |mut c: Caller<'_, ()>, h, l, n, b| {
+            let mem = memory(&mut c);
+            imports::get_many(mem.data_mut(&mut c), h, l, n, b).map_err(trap)
+        })
+        .unwrap();
+    linker
+}
+
+struct Instantiated {
+    store: WasmStore,
+    instance: Instance,
+    memory: wasmi::Memory,
+}
+
+impl Instantiated {
+    fn new(wat: &str) -> Self {
+        let engine = Engine::default();
+        let module =
+            Module::new(&engine, &wat::parse_str(wat).unwrap()[..]).unwrap();
+
+        let version = match module
+            .exports()
+            .any(|export| export.name() == abi::VERSION_EXPORT)
+        {
+            true => abi::VERSION,
+            false => 0,
+        };
+
+        imports::check_version(version).unwrap();
+
+        let mut store = WasmStore::new(&engine, ());
+        let instance = linker(&engine, version)
+            .instantiate(&mut store, &module)
+            .unwrap()
+            .start(&mut store)
+            .unwrap();
+
+        if version != 0 {
+            let reported = instance
+                .get_typed_func::<(), i32>(&store, abi::VERSION_EXPORT)
+                .unwrap()
+                .call(&mut store, ())
+                .unwrap();
+            assert_eq!(reported, version);
+        }
+
+        let memory = instance.get_memory(&store, "memory").unwrap();
+        Instantiated {
+            store,
+            instance,
+            memory,
+        }
+    }
+
+    fn write(&mut self, offset: usize, bytes: &[u8]) {
+        self.memory.write(&mut self.store, offset, bytes).unwrap();
+    }
+
+    fn read(&self, offset: usize, len: usize) -> Vec<u8> {
+        self.memory.data(&self.store)[offset..offset + len].to_vec()
+    }
+
+    fn call<P, R>(&mut self, name: &str, params: P) -> Result<R, wasmi::Error>
+    where
+        P: wasmi::WasmParams,
+        R: wasmi::WasmResults,
+    {
+        self.instance
+            .get_typed_func::<P, R>(&self.store, name)
+            .unwrap()
+            .call(&mut self.store, params)
+    }
+}
+
+#[test]
+fn put_get_hash() {
+    let mut wasm = Instantiated::new(MODULE);
+    let value = [7u8; 40];
+
+    let current = IdVersion::Blake2b.byte() as i32;
+
+    wasm.write(0, &value);
+    let version: i32 = wasm.call("call_put", (0, 40, 100)).unwrap();
+    assert_eq!(version, current);
+    assert_eq!(wasm.read(100, 32), Store::hash(&value));
+
+    let status: i32 = wasm.call("call_get", (100, 200, 40)).unwrap();
+    assert_eq!(status, abi::OK);
+    assert_eq!(wasm.read(200, 40), value);
+
+    let version: i32 = wasm.call("call_hash", (0, 40, 300)).unwrap();
+    assert_eq!(version, current);
+    assert_eq!(wasm.read(300, 32), Store::hash(&value));
+}
+
+#[test]
+#[cfg(not(all(feature = "mock-bridge", feature = "legacy-abi")))]
+fn get_status() {
+    let mut wasm = Instantiated::new(MODULE);
+
+    wasm.write(0, &[0xcc; 32]);
+    let status: i32 = wasm.call("call_get", (0, 100, 40)).unwrap();
+    assert_eq!(status, abi::NOT_FOUND);
+
+    let hash = Store::put(&[1; 50]);
+    wasm.write(0, &hash);
+    let status: i32 = wasm.call("call_get", (0, 100, 49)).unwrap();
+    assert_eq!(status, abi::CORRUPTED);
+}
+
+#[test]
+fn hash_versions() {
+    let mut wasm = Instantiated::new(MODULE);
+    let value = [5u8; 40];
+
+    let version: i32 = wasm.call("call_hash_version", ()).unwrap();
+    assert_eq!(version, IdVersion::Blake2b.byte() as i32);
+
+    wasm.write(0, &value);
+    let status: i32 =
+        wasm.call("call_hash_with", (version, 0, 40, 100)).unwrap();
+    assert_eq!(status, abi::OK);
+    assert_eq!(wasm.read(100, 32), Store::hash(&value));
+
+    for unknown in [IdVersion::Inline.byte() as i32, 0x90, 256, -1] {
+        let status: i32 =
+            wasm.call("call_hash_with", (unknown, 0, 40, 100)).unwrap();
+        assert_eq!(status, abi::NOT_FOUND);
+    }
+
+    assert!(imports::check_version(0).is_ok());
+    assert!(imports::check_version(abi::VERSION + 1).is_err());
+    assert!(imports::check_version(-1).is_err());
+}
+
+#[test]
+fn batched() {
+    let mut wasm = Instantiated::new(MODULE);
+    let values: [&[u8]; 3] = [&[1; 33], &[2; 70], &[3; 48]];
+
+    // the lengths at 0, the concatenated values at 100
+    let lens: Vec<u8> = values
+        .iter()
+        .flat_map(|v| (v.len() as i32).to_le_bytes())
+        .collect();
+    wasm.write(0, &lens);
+    wasm.write(100, &values.concat());
+
+    let version: i32 = wasm.call("call_put_many", (100, 0, 3, 400)).unwrap();
+    assert_eq!(version, IdVersion::Blake2b.byte() as i32);
+    let hashes = wasm.read(400, 3 * 32);
+    for (hash, value) in hashes.chunks(32).zip(&values) {
+        assert_eq!(hash, Store::hash(value));
+    }
+
+    let status: i32 = wasm.call("call_get_many", (400, 0, 3, 600)).unwrap();
+    assert_eq!(status, abi::OK);
+    assert_eq!(wasm.read(600, 151), values.concat());
+}
+
+#[test]
+fn out_of_bounds() {
+    let mut wasm = Instantiated::new(MODULE);
+
+    assert!(wasm.call::<_, i32>("call_put", (65530, 40, 0)).is_err());
+    assert!(wasm.call::<_, i32>("call_get", (0, 65530, 40)).is_err());
+    assert!(wasm.call::<_, i32>("call_hash", (-1, 40, 0)).is_err());
+
+    // lengths adding up to more than the memory of the module
+    let lens: Vec<u8> = [-1i32, -1, 8]
+        .iter()
+        .flat_map(|len| len.to_le_bytes())
+        .collect();
+    wasm.write(0, &lens);
+    assert!(wasm
+        .call::<_, i32>("call_put_many", (100, 0, 3, 400))
+        .is_err());
+    assert!(wasm
+        .call::<_, i32>("call_get_many", (400, 0, 3, 600))
+        .is_err());
+
+    // values are not stored when their hashes can not be returned
+    let value = [0x3a; 40];
+    let stored = || {
+        let hash = Store::hash(&value);
+        StoreRef::global().entries().any(|(h, _)| h == hash)
+    };
+    wasm.write(0, &value);
+    assert!(wasm.call::<_, i32>("call_put", (0, 40, 65530)).is_err());
+    assert!(!stored());
+
+    wasm.write(0, &40i32.to_le_bytes());
+    wasm.write(100, &value);
+    assert!(wasm
+        .call::<_, i32>("call_put_many", (100, 0, 1, 65530))
+        .is_err());
+    assert!(!stored());
+}
+
+#[test]
+fn legacy() {
+    let mut wasm = Instantiated::new(LEGACY_MODULE);
+    let value = [9u8; 40];
+
+    wasm.write(0, &value);
+    wasm.call::<_, ()>("call_put", (0, 40, 100)).unwrap();
+    wasm.call::<_, ()>("call_get", (100, 200, 40)).unwrap();
+    assert_eq!(wasm.read(200, 40), value);
+
+    wasm.call::<_, ()>("call_hash", (0, 40, 300)).unwrap();
+    assert_eq!(wasm.read(300, 32), Store::hash(&value));
+
+    // failing gets trap
+    #[cfg(not(all(feature = "mock-bridge", feature = "legacy-abi")))]
+    {
+        wasm.write(100, &[0xcc; 32]);
+        assert!(wasm.call::<_, ()>("call_get", (100, 200, 40)).is_err());
+    }
+}
@@ -13,7 +13,6 @@ fn test_u8() {
     for i in 0..u8::MAX {
         let mut sink = Sink::new(&mut buf);
         i.encode(&mut sink);
-        drop(sink);
         let mut source = Source::new(&buf);
         assert_eq!(i, u8::decode(&mut source).unwrap());
     }
@@ -26,7 +25,6 @@ fn test_u16() {
     for i in 0..u16::MAX {
         let mut sink = Sink::new(&mut buf);
         i.encode(&mut sink);
-        drop(sink);
         let mut source = Source::new(&buf);
         assert_eq!(i, u16::decode(&mut source).unwrap());
     }
@@ -44,7 +42,6 @@ fn test_u32() {
 
         let mut sink = Sink::new(&mut buf);
         i.encode(&mut sink);
-        drop(sink);
         let mut source = Source::new(&buf);
         assert_eq!(i, u32::decode(&mut source).unwrap());
     }
@@ -62,7 +59,6 @@ fn test_u64() {
 
         let mut sink = Sink::new(&mut buf);
         i.encode(&mut sink);
-        drop(sink);
         let mut source = Source::new(&buf);
         assert_eq!(i, u64::decode(&mut source).unwrap());
     }
@@ -80,7 +76,6 @@ fn test_u128() {
 
         let mut sink = Sink::new(&mut buf);
         i.encode(&mut sink);
-        drop(sink);
         let mut source = Source::new(&buf);
         assert_eq!(i, u128::decode(&mut source).unwrap());
     }
@@ -98,7 +93,6 @@ fn test_i64() {
 
         let mut sink = Sink::new(&mut buf);
         i.encode(&mut sink);
-        drop(sink);
         let mut source = Source::new(&buf);
         assert_eq!(i, i64::decode(&mut source).unwrap());
     }
@@ -116,7 +110,6 @@ fn test_i128() {
 
         let mut sink = Sink::new(&mut buf);
         i.encode(&mut sink);
-        drop(sink);
         let mut source = Source::new(&buf);
         assert_eq!(i, i128::decode(&mut source).unwrap());
     }
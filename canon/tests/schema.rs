@@ -0,0 +1,82 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use canonical::{CanonSchema, Endian, FixedBE, Schema};
+
+#[test]
+fn integers() {
+    assert_eq!(u8::schema(), Schema::U8);
+    assert_eq!(
+        i128::schema(),
+        Schema::Varint {
+            bits: 128,
+            signed: true
+        }
+    );
+    assert_eq!(
+        FixedBE::<u32>::schema(),
+        Schema::Fixed {
+            bits: 32,
+            signed: false,
+            endian: Endian::Big
+        }
+    );
+}
+
+#[test]
+fn containers() {
+    assert_eq!(
+        <[Option<bool>; 3]>::schema(),
+        Schema::Array(3, Box::new(Schema::Option(Box::new(Schema::Bool))))
+    );
+    assert_eq!(
+        <(u8, String)>::schema(),
+        Schema::Tuple(vec![Schema::U8, Schema::String])
+    );
+    assert_eq!(
+        BTreeMap::<String, ()>::schema(),
+        Schema::Map(Box::new(Schema::String), Box::new(Schema::Unit))
+    );
+}
+
+#[test]
+fn json() {
+    assert_eq!(
+        <Vec<Result<u16, FixedBE<i8>>>>::schema().to_json(),
+        concat!(
+            r#"{"type":"sequence","item":{"type":"result","#,
+            r#""ok":{"type":"varint","bits":16,"signed":false},"#,
+            r#""err":{"type":"fixed","bits":8,"signed":true,"endian":"big"}}}"#,
+        )
+    );
+
+    assert_eq!(
+        Duration::schema().to_json(),
+        concat!(
+            r#"{"type":"struct","name":"Duration","kind":"named","fields":["#,
+            r#"{"name":"secs","schema":"#,
+            r#"{"type":"varint","bits":64,"signed":false}},"#,
+            r#"{"name":"nanos","schema":"#,
+            r#"{"type":"varint","bits":32,"signed":false}}]}"#,
+        )
+    );
+}
+
+#[test]
+fn json_escapes_names() {
+    let schema = Schema::Struct {
+        name: "a\"b\\c\n".into(),
+        fields: canonical::Fields::Unit,
+    };
+
+    assert_eq!(
+        schema.to_json(),
+        r#"{"type":"struct","name":"a\"b\\c\u000a","kind":"unit","fields":[]}"#
+    );
+}
@@ -0,0 +1,133 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use canonical::{
+    Backend, CanonError, Id, IdHash, MemoryBackend, Store, StoreRef,
+};
+
+fn value(n: u8) -> Vec<u8> {
+    vec![n; 64]
+}
+
+fn not_found<T: std::fmt::Debug>(result: Result<T, CanonError>) -> bool {
+    matches!(result, Err(CanonError::NotFound))
+}
+
+#[test]
+fn scoped_store_is_isolated() {
+    let store = StoreRef::memory();
+
+    let id = Store::scoped(store.clone(), || Id::new(&value(1)));
+
+    #[cfg(not(all(feature = "mock-bridge", feature = "legacy-abi")))]
+    assert!(not_found(id.reify::<Vec<u8>>()));
+    assert_eq!(id.reify_in::<Vec<u8>>(&store).unwrap(), value(1));
+    Store::scoped(store, || {
+        assert_eq!(id.reify::<Vec<u8>>().unwrap(), value(1));
+    });
+}
+
+#[test]
+fn new_in_and_reify_in() {
+    let a = StoreRef::memory();
+    let b = StoreRef::memory();
+
+    let id = Id::new_in(&value(2), &a);
+    assert_eq!(id, Id::new_in(&value(2), &b));
+
+    let id = Id::new_in(&value(3), &a);
+    assert_eq!(id.reify_in::<Vec<u8>>(&a).unwrap(), value(3));
+    assert!(not_found(id.reify_in::<Vec<u8>>(&b)));
+    #[cfg(not(all(feature = "mock-bridge", feature = "legacy-abi")))]
+    assert!(not_found(id.reify_in::<Vec<u8>>(&StoreRef::global())));
+}
+
+#[test]
+fn scopes_nest_and_restore() {
+    let outer = StoreRef::memory();
+    let inner = StoreRef::memory();
+
+    assert!(StoreRef::current().same(&StoreRef::global()));
+
+    Store::scoped(outer.clone(), || {
+        assert!(StoreRef::current().same(&outer));
+        Store::scoped(inner.clone(), || {
+            assert!(StoreRef::current().same(&inner));
+        });
+        assert!(StoreRef::current().same(&outer));
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            Store::scoped(inner.clone(), || panic!("in scope"))
+        }));
+        assert!(result.is_err());
+        assert!(StoreRef::current().same(&outer));
+    });
+
+    assert!(StoreRef::current().same(&StoreRef::global()));
+}
+
+#[test]
+fn scopes_are_per_thread() {
+    let store = StoreRef::memory();
+
+    Store::scoped(store.clone(), || {
+        let other =
+            thread::spawn(|| StoreRef::current().same(&StoreRef::global()));
+        assert!(other.join().unwrap());
+        assert!(StoreRef::current().same(&store));
+    });
+}
+
+#[derive(Default)]
+struct Counting {
+    inner: MemoryBackend,
+    puts: Arc<AtomicUsize>,
+}
+
+impl Backend for Counting {
+    fn get(&self, hash: &IdHash, into: &mut [u8]) -> Result<(), CanonError> {
+        self.inner.get(hash, into)
+    }
+
+    fn put(&self, hash: IdHash, bytes: &[u8]) {
+        self.puts.fetch_add(1, Ordering::SeqCst);
+        self.inner.put(hash, bytes)
+    }
+
+    fn remove(&self, hash: &IdHash) -> Option<Vec<u8>> {
+        self.inner.remove(hash)
+    }
+}
+
+#[test]
+fn custom_backend() {
+    let backend = Counting::default();
+    let puts = backend.puts.clone();
+
+    Store::scoped(backend, || {
+        let ids: Vec<_> = (0..10).map(|n| Id::new(&value(n))).collect();
+        let more = Id::new_many(&[value(10), value(11)]);
+
+        for (n, id) in ids.iter().chain(&more).enumerate() {
+            assert_eq!(id.reify::<Vec<u8>>().unwrap(), value(n as u8));
+        }
+        // a corrupted take leaves the entry in the store
+        let wrong = Id::raw(ids[0].hash(), 64);
+        assert!(matches!(wrong.take_bytes(), Err(CanonError::Corrupted)));
+        assert_eq!(ids[0].reify::<Vec<u8>>().unwrap(), value(0));
+
+        assert_eq!(ids[0].take_bytes().unwrap().unwrap().len(), 65);
+        assert!(not_found(ids[0].reify::<Vec<u8>>()));
+    });
+
+    // with the entry put back after the corrupted take
+    assert_eq!(puts.load(Ordering::SeqCst), 13);
+}
@@ -0,0 +1,223 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+#![cfg(feature = "serde")]
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+use std::time::{Duration, Instant};
+
+use canonical::serde::{from_slice, to_vec, Error};
+use canonical::{Canon, CanonError, EncodeToVec, Id};
+use canonical_derive::Canon;
+use serde::{Deserialize, Serialize, Serializer};
+
+#[derive(Clone, Canon, Serialize, Deserialize, PartialEq, Debug)]
+struct A {
+    a: u64,
+    b: i32,
+    c: u8,
+}
+
+#[derive(Clone, Canon, Serialize, Deserialize, PartialEq, Debug)]
+struct B(u16, Vec<u8>);
+
+#[derive(Clone, Canon, Serialize, Deserialize, PartialEq, Debug)]
+struct C(String);
+
+#[derive(Clone, Canon, Serialize, Deserialize, PartialEq, Debug)]
+struct D;
+
+#[derive(Clone, Canon, Serialize, Deserialize, PartialEq, Debug)]
+enum E {
+    A,
+    B(Option<u32>),
+    C(i64, bool),
+    D { alice: u128, bob: [u16; 3] },
+}
+
+#[derive(Clone, Canon, Serialize, Deserialize, PartialEq, Debug)]
+struct Monster {
+    a: A,
+    b: B,
+    c: C,
+    d: D,
+    e: Vec<E>,
+    map: BTreeMap<String, Result<u64, i16>>,
+    set: BTreeSet<u32>,
+    tuple: (u8, (i128,), ()),
+    hash: [u8; 32],
+    id: Id,
+}
+
+fn cross_check<T>(t: T)
+where
+    T: Canon + Serialize + for<'de> Deserialize<'de> + PartialEq + Debug,
+{
+    let canon = t.encode_to_vec();
+    let serde = to_vec(&t).unwrap();
+
+    assert_eq!(canon, serde, "{:?}", t);
+    assert_eq!(from_slice::<T>(&canon).unwrap(), t);
+}
+
+#[test]
+fn primitives() {
+    for i in [0u64, 1, 127, 128, 300, u64::MAX] {
+        cross_check(i);
+        cross_check(i as u16);
+        cross_check(i as u32);
+        cross_check(i as i16);
+        cross_check(i as i32);
+        cross_check(i as i64);
+        cross_check(i as u8);
+        cross_check((i as u128) << 64 | 7);
+        cross_check(-((i as i128) << 60));
+    }
+    cross_check(true);
+    cross_check(false);
+    cross_check(());
+    cross_check(String::from("hello, world"));
+}
+
+#[test]
+fn containers() {
+    cross_check(Some(3u16));
+    cross_check(None::<u16>);
+    cross_check(Ok::<u32, String>(8));
+    cross_check(Err::<u32, String>("nope".into()));
+    cross_check(vec![1u8, 2, 3]);
+    cross_check(vec![300u32; 200]);
+    cross_check([7u64; 4]);
+    cross_check((1u8, 2u16, 3u32));
+}
+
+#[test]
+fn derived() {
+    let mut map = BTreeMap::new();
+    map.insert("ok".into(), Ok(1 << 40));
+    map.insert("err".into(), Err(-3));
+
+    cross_check(Monster {
+        a: A {
+            a: 37,
+            b: -77,
+            c: 255,
+        },
+        b: B(1024, vec![0xde, 0xad]),
+        c: C("Happy happy joy joy!".into()),
+        d: D,
+        e: vec![
+            E::A,
+            E::B(None),
+            E::B(Some(9)),
+            E::C(-1, true),
+            E::D {
+                alice: u128::MAX,
+                bob: [1, 2, 3],
+            },
+        ],
+        map,
+        set: (0..100).collect(),
+        tuple: (1, (-5,), ()),
+        hash: [0xab; 32],
+        id: Id::new(&String::from("referenced")),
+    });
+}
+
+#[test]
+fn ids() {
+    cross_check(Id::new(&vec![3u64; 20]));
+    cross_check(Id::new_inline(&7u32).unwrap());
+    cross_check(Id::new_inline(&()).unwrap());
+    cross_check(Id::new_inline(&[9u8; 32]).unwrap());
+    cross_check(vec![Id::new_inline(&1u8).unwrap(), Id::new(&[1u8; 40])]);
+
+    assert_eq!(to_vec(&Id::new_inline(&7u32).unwrap()).unwrap(), [1, 1, 7]);
+
+    // truncated ids are rejected
+    let hashed = Id::new(&[1u8; 40]).encode_to_vec();
+    let truncated: [&[u8]; 6] = [
+        &[],
+        &[0, 5],
+        &[0, 0x80],
+        &[5, 0x80, 0x80],
+        &[1, 3, 7],
+        &hashed[..hashed.len() - 1],
+    ];
+    for bytes in truncated {
+        assert!(matches!(
+            from_slice::<Id>(bytes),
+            Err(Error::Canon(CanonError::InvalidEncoding))
+        ));
+    }
+}
+
+#[test]
+fn ids_in_other_formats() {
+    let hashed = Id::new(&vec![3u64; 20]);
+    let inline = Id::new_inline(&7u32).unwrap();
+    let empty = Id::new_inline(&()).unwrap();
+
+    for id in [hashed, inline, empty] {
+        let bytes = bincode::serialize(&(id, 5u8)).unwrap();
+        assert_eq!(bytes.len(), 1 + 4 + 32 + 1);
+        let (decoded, next): (Id, u8) = bincode::deserialize(&bytes).unwrap();
+        assert_eq!((decoded, next), (id, 5));
+    }
+
+    // the padding of inline ids is checked
+    let mut bytes = bincode::serialize(&inline).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] = 1;
+    assert!(bincode::deserialize::<Id>(&bytes).is_err());
+}
+
+#[test]
+fn unknown_length() {
+    struct Evens(u32);
+
+    impl Serialize for Evens {
+        fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            s.collect_seq((0..self.0).filter(|i| i % 2 == 0))
+        }
+    }
+
+    let expected: Vec<u32> = (0..1000).filter(|i| i % 2 == 0).collect();
+    assert_eq!(to_vec(&Evens(1000)).unwrap(), expected.encode_to_vec());
+}
+
+#[test]
+fn large_input() {
+    let ints: Vec<u32> = (0..200_000).map(|i| u32::MAX - i).collect();
+    let bytes = ints.encode_to_vec();
+
+    // decoding used to scan the rest of the input for every integer
+    let start = Instant::now();
+    assert_eq!(from_slice::<Vec<u32>>(&bytes).unwrap(), ints);
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn unsupported() {
+    assert!(matches!(to_vec(&1.5f64), Err(Error::Unsupported(_))));
+    assert!(matches!(to_vec(&'c'), Err(Error::Unsupported(_))));
+    assert!(matches!(to_vec(&-1i8), Err(Error::Unsupported(_))));
+}
+
+#[test]
+fn invalid() {
+    let bytes = (String::from("hello"), 1u64 << 40).encode_to_vec();
+
+    for len in 0..bytes.len() {
+        assert!(from_slice::<(String, u64)>(&bytes[..len]).is_err());
+    }
+
+    assert!(from_slice::<bool>(&[2]).is_err());
+    assert!(from_slice::<E>(&[4]).is_err());
+    assert!(from_slice::<u8>(&[1, 2]).is_err());
+    assert!(from_slice::<Vec<u8>>(&u64::MAX.encode_to_vec()).is_err());
+}
@@ -0,0 +1,170 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::cmp::Reverse;
+use std::sync::Arc;
+
+use canonical::{
+    Backend, CanonError, Id, IdHash, MemoryBackend, Store, StoreRef, StoreStats,
+};
+
+#[test]
+fn memory_stats() {
+    let store = StoreRef::memory();
+    assert_eq!(store.stats(), StoreStats::default());
+
+    let small = Id::new_in(&vec![1u8; 40], &store);
+    let large = Id::new_in(&vec![2u8; 100], &store);
+    let missing = Id::new_in(&vec![3u8; 60], &StoreRef::memory());
+
+    small.reify_in::<Vec<u8>>(&store).unwrap();
+    large.reify_in::<Vec<u8>>(&store).unwrap();
+    large.reify_in::<Vec<u8>>(&store).unwrap();
+    assert!(missing.reify_in::<Vec<u8>>(&store).is_err());
+
+    // reading an entry with another length is not a hit
+    let corrupted = Id::raw(small.hash(), 40);
+    assert!(matches!(
+        corrupted.reify_in::<Vec<u8>>(&store),
+        Err(CanonError::Corrupted)
+    ));
+
+    assert_eq!(
+        store.stats(),
+        StoreStats {
+            entries: 2,
+            bytes: 41 + 101,
+            largest: 101,
+            hits: 3,
+            misses: 2,
+        }
+    );
+}
+
+#[test]
+fn entries() {
+    let store = StoreRef::memory();
+    let ids: Vec<_> = (1..5u8)
+        .map(|n| Id::new_in(&vec![n; n as usize * 40], &store))
+        .collect();
+
+    let mut entries: Vec<_> = store.entries().collect();
+    let mut expected: Vec<_> =
+        ids.iter().map(|id| (id.hash(), id.size())).collect();
+    entries.sort();
+    expected.sort();
+    assert_eq!(entries, expected);
+
+    let mut largest: Vec<_> = store.entries().collect();
+    largest.sort_by_key(|(_, size)| Reverse(*size));
+    assert_eq!(largest[0], (ids[3].hash(), ids[3].size()));
+}
+
+#[test]
+fn largest() {
+    let store = StoreRef::memory();
+    let ids: Vec<_> = (1..5u8)
+        .map(|n| Id::new_in(&vec![n; n as usize * 40], &store))
+        .collect();
+
+    let expected: Vec<_> =
+        ids.iter().rev().map(|id| (id.hash(), id.size())).collect();
+    assert_eq!(store.largest(2), expected[..2]);
+    assert_eq!(store.largest(10), expected);
+    assert!(store.largest(0).is_empty());
+
+    // the default implementation sorts the entries
+    let listing = StoreRef::new(Listing::default());
+    for id in &ids {
+        let mut bytes = vec![0u8; id.size()];
+        store.get(&id.hash(), &mut bytes).unwrap();
+        listing.put(&bytes);
+    }
+    assert_eq!(listing.largest(3), store.largest(3));
+}
+
+#[test]
+fn removed_entries() {
+    let backend = Arc::new(MemoryBackend::new());
+    let store = StoreRef::new(backend.clone());
+    let small = Id::new_in(&vec![1u8; 40], &store);
+    let large = Id::new_in(&vec![2u8; 100], &store);
+
+    // putting an entry again does not count it twice
+    Id::new_in(&vec![2u8; 100], &store);
+    assert_eq!((store.stats().entries, store.stats().bytes), (2, 142));
+
+    backend.remove(&large.hash());
+    assert_eq!(store.stats().largest, 41);
+    assert_eq!(store.largest(2), vec![(small.hash(), 41)]);
+
+    backend.remove(&small.hash());
+    assert_eq!(store.stats(), StoreStats::default());
+}
+
+#[test]
+fn scoped_stats() {
+    let store = StoreRef::memory();
+    Store::scoped(store.clone(), || {
+        Id::new(&[7u8; 64]).reify::<[u8; 64]>().unwrap();
+
+        assert_eq!(Store::stats(), store.stats());
+        assert_eq!(Store::entries().count(), 1);
+    });
+    assert_eq!(store.stats().hits, 1);
+}
+
+#[test]
+fn global_stats() {
+    let id = Id::new(&[9u8; 64]);
+    let before = Store::stats();
+    id.reify::<[u8; 64]>().unwrap();
+    let after = Store::stats();
+
+    assert!(after.entries >= 1);
+    assert!(after.hits > before.hits);
+    assert!(Store::entries().any(|entry| entry == (id.hash(), 64)));
+}
+
+// A backend listing its entries, but not counting gets
+#[derive(Default)]
+struct Listing(MemoryBackend);
+
+impl Backend for Listing {
+    fn get(&self, hash: &IdHash, into: &mut [u8]) -> Result<(), CanonError> {
+        self.0.get(hash, into)
+    }
+
+    fn put(&self, hash: IdHash, bytes: &[u8]) {
+        self.0.put(hash, bytes)
+    }
+
+    fn remove(&self, hash: &IdHash) -> Option<Vec<u8>> {
+        self.0.remove(hash)
+    }
+
+    fn entries(&self) -> Vec<(IdHash, usize)> {
+        self.0.entries()
+    }
+}
+
+#[test]
+fn default_stats() {
+    let store = StoreRef::new(Listing::default());
+    let id = Id::new_in(&[5u8; 50], &store);
+    id.reify_in::<[u8; 50]>(&store).unwrap();
+
+    assert_eq!(
+        store.stats(),
+        StoreStats {
+            entries: 1,
+            bytes: 50,
+            largest: 50,
+            hits: 0,
+            misses: 0,
+        }
+    );
+}
@@ -0,0 +1,44 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use canonical::{Canon, EncodeToVec, Id};
+use canonical_fuzz::fuzz_canon;
+
+#[test]
+fn single_element_tuple() {
+    let tuple = (1337u64,);
+
+    assert_eq!(tuple.encode_to_vec(), 1337u64.encode_to_vec());
+    assert_eq!(tuple.encoded_len(), 1337u64.encoded_len());
+    assert_eq!(Id::new(&tuple).reify::<(u64,)>().unwrap(), tuple);
+}
+
+#[test]
+fn fuzz_single_element_tuple() {
+    fuzz_canon::<(u8,)>();
+    fuzz_canon::<(u64,)>();
+    fuzz_canon::<(String,)>();
+    fuzz_canon::<((u32,),)>();
+    fuzz_canon::<Vec<(Option<u16>,)>>();
+}
+
+#[test]
+fn fuzz_twelve_element_tuple() {
+    fuzz_canon::<(
+        u8,
+        u16,
+        u32,
+        u64,
+        u128,
+        i16,
+        i32,
+        i64,
+        i128,
+        bool,
+        (),
+        Option<u8>,
+    )>();
+}
@@ -0,0 +1,127 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use canonical::{
+    CanonSchema, EncodeToVec, FixedBE, FixedLE, Id, Schema, Value,
+};
+
+fn value<T: canonical::Canon + CanonSchema>(t: &T) -> Value {
+    Value::from_bytes(&T::schema(), &t.encode_to_vec()).unwrap()
+}
+
+#[test]
+fn integers() {
+    assert_eq!(value(&200u8), Value::UInt(200));
+    assert_eq!(value(&u64::MAX), Value::UInt(u64::MAX.into()));
+    assert_eq!(value(&i128::MIN), Value::Int(i128::MIN));
+    assert_eq!(value(&FixedLE(-2i16)), Value::Int(-2));
+    assert_eq!(value(&FixedBE(-2i32)), Value::Int(-2));
+    assert_eq!(value(&FixedBE(u128::MAX)), Value::UInt(u128::MAX));
+}
+
+#[test]
+fn collections() {
+    let mut map = BTreeMap::new();
+    map.insert(String::from("a"), Some(1u32));
+    map.insert(String::from("b"), None);
+
+    assert_eq!(
+        value(&map),
+        Value::Map(vec![
+            (
+                Value::String("a".into()),
+                Value::Option(Some(Box::new(Value::UInt(1))))
+            ),
+            (Value::String("b".into()), Value::Option(None)),
+        ])
+    );
+
+    assert_eq!(value(&[7u8; 3]), Value::Bytes(vec![7; 3]));
+    assert_eq!(
+        value(&vec![1u16, 2]),
+        Value::Seq(vec![Value::UInt(1), Value::UInt(2)])
+    );
+}
+
+#[test]
+fn truncated() {
+    let bytes = (String::from("hello"), 1u64 << 40).encode_to_vec();
+    let schema = <(String, u64)>::schema();
+
+    for len in 0..bytes.len() {
+        assert!(Value::from_bytes(&schema, &bytes[..len]).is_err());
+    }
+    assert!(Value::from_bytes(&schema, &bytes).is_ok());
+}
+
+#[test]
+fn huge_length() {
+    let bytes = u64::MAX.encode_to_vec();
+
+    assert!(Value::from_bytes(&Schema::String, &bytes).is_err());
+    assert!(Value::from_bytes(&Vec::<u32>::schema(), &bytes).is_err());
+}
+
+#[test]
+fn huge_length_of_empty_items() {
+    let bytes = u64::MAX.encode_to_vec();
+    assert!(Value::from_bytes(&Vec::<()>::schema(), &bytes).is_err());
+    assert!(Value::from_bytes(&BTreeMap::<(), ()>::schema(), &bytes).is_err());
+
+    let bytes = 50_000_000u64.encode_to_vec();
+    assert!(Value::from_bytes(&Vec::<()>::schema(), &bytes).is_err());
+
+    let units = vec![(); 1000];
+    assert_eq!(value(&units), Value::Seq(vec![Value::Unit; 1000]));
+
+    // the limit holds for all the sequences of a value together
+    let nested = vec![vec![(); 1 << 15]; 2];
+    let bytes = nested.encode_to_vec();
+    assert!(Value::from_bytes(&Vec::<Vec<()>>::schema(), &bytes).is_ok());
+
+    let nested = vec![vec![(); 1 << 15]; 3];
+    let bytes = nested.encode_to_vec();
+    assert!(Value::from_bytes(&Vec::<Vec<()>>::schema(), &bytes).is_err());
+
+    let mut bytes = 1000u64.encode_to_vec();
+    for _ in 0..1000 {
+        bytes.extend(65_536u64.encode_to_vec());
+    }
+    assert!(Value::from_bytes(&Vec::<Vec<()>>::schema(), &bytes).is_err());
+}
+
+#[test]
+fn large_input() {
+    let ints: Vec<u32> = (0..200_000).map(|i| u32::MAX - i).collect();
+    let bytes = ints.encode_to_vec();
+
+    // decoding used to scan the rest of the input for every integer
+    let start = Instant::now();
+    let value = Value::from_bytes(&Vec::<u32>::schema(), &bytes).unwrap();
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(
+        value,
+        Value::Seq(ints.iter().map(|i| Value::UInt((*i).into())).collect())
+    );
+}
+
+#[test]
+fn display_id() {
+    let id = Id::raw([0xab; 32], 40);
+
+    assert_eq!(value(&id), Value::Id(id));
+    assert_eq!(format!("{}", value(&id)), format!("{:?}", id));
+
+    let inline = Id::new_inline(&7u32).unwrap();
+    assert_eq!(
+        value(&(inline, 1u8)),
+        Value::Tuple(vec![Value::Id(inline), Value::UInt(1)])
+    );
+    assert_eq!(value(&inline), Value::Id(inline));
+}
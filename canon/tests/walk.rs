@@ -0,0 +1,165 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::collections::BTreeMap;
+
+use canonical::{
+    Canon, CanonError, Id, Order, Source, Store, StoreRef, VisitIds, Walk,
+};
+
+// A node of a graph, with a label and the ids of its children
+type Node = (u32, Vec<Id>);
+
+fn node(label: u32, children: Vec<Id>) -> Id {
+    Id::new(&(label, children))
+}
+
+fn labels(
+    walk: impl Iterator<Item = Result<(Id, Vec<u8>), CanonError>>,
+) -> Vec<u32> {
+    walk.map(|item| {
+        let (_, bytes) = item.unwrap();
+        Node::decode(&mut Source::new(&bytes)).unwrap().0
+    })
+    .collect()
+}
+
+//        0
+//      /   \
+//     1     2
+//    / \   / \
+//   3   4 4   5
+fn graph() -> Id {
+    let four = node(4, vec![]);
+    let one = node(1, vec![node(3, vec![]), four]);
+    let two = node(2, vec![four, node(5, vec![])]);
+    node(0, vec![one, two])
+}
+
+#[test]
+fn orders() {
+    let root = graph();
+
+    let depth = Walk::of::<Node>(&[root], Order::DepthFirst);
+    assert_eq!(labels(depth), vec![0, 1, 3, 4, 2, 5]);
+
+    let breadth = Walk::of::<Node>(&[root], Order::BreadthFirst);
+    assert_eq!(labels(breadth), vec![0, 1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn several_roots() {
+    let a = node(10, vec![node(11, vec![])]);
+    let b = node(20, vec![a]);
+
+    let walk = Walk::of::<Node>(&[a, b, a], Order::DepthFirst);
+    assert_eq!(labels(walk), vec![10, 11, 20]);
+}
+
+#[test]
+fn inline_ids() {
+    let inline = Id::new_inline(&(7u32, Vec::<Id>::new())).unwrap();
+    let root = node(0, vec![inline]);
+
+    let walk = Walk::of::<Node>(&[root], Order::BreadthFirst);
+    assert_eq!(labels(walk), vec![0, 7]);
+}
+
+#[test]
+fn custom_children() {
+    let root = graph();
+
+    // only follow the first child
+    let walk = Walk::new(&[root], Order::DepthFirst, |_, bytes| {
+        let (_, children) = Node::decode(&mut Source::new(bytes))?;
+        Ok(children.into_iter().take(1).collect())
+    });
+    assert_eq!(labels(walk), vec![0, 1, 3]);
+}
+
+#[test]
+fn missing_values() {
+    let other = StoreRef::memory();
+    let missing = Id::new_in(&(9u32, Vec::<Id>::new()), &other);
+    let root = Id::new_in(&(0u32, vec![missing]), &other);
+
+    let ids = Store::scoped(other, || {
+        Walk::of::<Node>(&[root], Order::DepthFirst)
+            .map(|item| item.map(|(id, _)| id))
+            .collect::<Result<Vec<_>, _>>()
+    });
+    assert_eq!(ids.unwrap(), vec![root, missing]);
+
+    let store = StoreRef::memory();
+    let root = Id::new_in(&(0u32, vec![missing]), &store);
+    let mut walk = Store::scoped(store.clone(), || {
+        Walk::of::<Node>(&[root], Order::DepthFirst).collect::<Vec<_>>()
+    })
+    .into_iter();
+    assert!(walk.next().unwrap().is_ok());
+    assert!(matches!(walk.next(), Some(Err(CanonError::NotFound))));
+    assert!(walk.next().is_none());
+}
+
+#[test]
+fn std_types() {
+    let ids: Vec<Id> = (0..4u8).map(|i| Id::new(&[i; 40])).collect();
+
+    let mut map = BTreeMap::new();
+    map.insert(ids[1], Some(ids[2]));
+
+    let value = (
+        ids[3],
+        vec![Ok::<_, Id>(ids[0])],
+        [map, BTreeMap::new()],
+        1u64,
+    );
+    assert_eq!(value.ids(), vec![ids[3], ids[0], ids[1], ids[2]]);
+}
+
+#[test]
+fn mixed_types() {
+    #[derive(Clone, Copy)]
+    enum Kind {
+        Root,
+        List,
+        Node,
+    }
+
+    // a pair of a list of nodes and a node
+    let leaf = node(1, vec![]);
+    let list = Id::new(&vec![node(0, vec![leaf])]);
+    let root = Id::new(&(list, leaf));
+
+    // the type of each id is known from its parent
+    let mut kinds = BTreeMap::new();
+    kinds.insert(root, Kind::Root);
+
+    let walk = Walk::new(&[root], Order::DepthFirst, |id, bytes| {
+        let source = &mut Source::new(bytes);
+        let children = match kinds[id] {
+            Kind::Root => {
+                let (list, node) = <(Id, Id)>::decode(source)?;
+                vec![(list, Kind::List), (node, Kind::Node)]
+            }
+            Kind::List => Vec::<Id>::decode(source)?
+                .into_iter()
+                .map(|id| (id, Kind::Node))
+                .collect(),
+            Kind::Node => Node::decode(source)?
+                .1
+                .into_iter()
+                .map(|id| (id, Kind::Node))
+                .collect(),
+        };
+        kinds.extend(children.iter().copied());
+        Ok(children.into_iter().map(|(id, _)| id).collect())
+    });
+    let ids = walk
+        .map(|item| item.map(|(id, _)| id))
+        .collect::<Result<Vec<_>, _>>();
+    assert_eq!(ids.unwrap(), vec![root, list, node(0, vec![leaf]), leaf]);
+}
@@ -0,0 +1,295 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Command-line tool to inspect Canonical encodings and stores.
+//!
+//! Stores are inspected through archives, as written by
+//! `canonical::archive::export`.
+
+use std::convert::TryFrom;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::OnceLock;
+
+use canonical::archive;
+use canonical::{
+    Canon, EncodeToVec, HashAlgorithm, HashConfig, Id, IdHash, Source, Store,
+    StoreRef,
+};
+
+const USAGE: &str = "\
+Usage:
+    canon [options] hash <file>       Hash a file like the host store does
+    canon dump <hex>                  Dump a hex blob as a sequence of varints
+    canon [options] list <archive>    List the entries of an archive
+    canon [options] verify <archive>  Verify the entries of an archive
+    canon id <hash> <len>             Print an id given its hash and length
+    canon id <id>                     Print an id given its string form
+    canon [options] id --file <file>  Print the id of a file's contents
+
+Options:
+    --algorithm <name>    Hash with blake2b, the default, or blake3 and sha256
+                          when built with the blake3 and sha2 features
+    --domain <id>:<tag>   Hash under the domain <tag>, with an id below 128";
+
+type Result<T> = std::result::Result<T, String>;
+
+// The tag given with `--domain`, kept for the run as the hash configuration
+// refers to it
+static DOMAIN: OnceLock<String> = OnceLock::new();
+
+enum Command {
+    Hash(PathBuf),
+    Dump(String),
+    List(PathBuf),
+    Verify(PathBuf),
+    Id(Id),
+    IdOfFile(PathBuf),
+}
+
+impl Command {
+    fn parse(args: &[String]) -> Option<Self> {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        Some(match args[..] {
+            ["hash", file] => Command::Hash(file.into()),
+            ["dump", ref hex @ ..] if !hex.is_empty() => {
+                Command::Dump(hex.concat())
+            }
+            ["list", archive] => Command::List(archive.into()),
+            ["verify", archive] => Command::Verify(archive.into()),
+            ["id", "--file", file] => Command::IdOfFile(file.into()),
+            ["id", id] => Command::Id(id.parse().ok()?),
+            ["id", hash, len] => {
+                let hash = parse_hash(hash)?;
+                let len = len.parse().ok()?;
+                Command::Id(Id::raw(hash, len))
+            }
+            _ => return None,
+        })
+    }
+
+    fn run(self) -> Result<bool> {
+        match self {
+            Command::Hash(file) => {
+                let bytes = read(&file)?;
+                println!("{}", to_hex(&Store::hash(&bytes)));
+            }
+            Command::Dump(hex) => dump(&from_hex(&hex)?),
+            Command::List(file) => {
+                let (roots, store) = import(&file)?;
+                for root in &roots {
+                    println!("root {}", root);
+                }
+                let entries: Vec<_> = store.entries().collect();
+                let total: usize = entries.iter().map(|(_, len)| len).sum();
+                for (hash, len) in &entries {
+                    println!("{}  {}", to_hex(hash), len);
+                }
+                println!("{} entries, {} bytes", entries.len(), total);
+            }
+            Command::Verify(file) => return verify(&file),
+            Command::Id(id) => print_id(&id),
+            Command::IdOfFile(file) => print_id(&id_of(&read(&file)?)?),
+        }
+        Ok(true)
+    }
+}
+
+// Takes the hash configuration options out of the arguments
+fn hash_config(args: &mut Vec<String>) -> Option<HashConfig> {
+    let mut config = HashConfig::DEFAULT;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--algorithm" => {
+                config.algorithm = parse_algorithm(args.get(i + 1)?)?;
+            }
+            "--domain" => {
+                let (id, domain) = args.get(i + 1)?.split_once(':')?;
+                let id = id.parse().ok().filter(|id| *id < 0x80)?;
+                // only one domain can be given
+                DOMAIN.set(domain.into()).ok()?;
+                config = config.with_domain(id, DOMAIN.get()?.as_bytes());
+            }
+            _ => {
+                i += 1;
+                continue;
+            }
+        }
+        args.drain(i..i + 2);
+    }
+    Some(config)
+}
+
+fn parse_algorithm(name: &str) -> Option<HashAlgorithm> {
+    match name {
+        "blake2b" => Some(HashAlgorithm::Blake2b),
+        #[cfg(feature = "blake3")]
+        "blake3" => Some(HashAlgorithm::Blake3),
+        #[cfg(feature = "sha2")]
+        "sha256" => Some(HashAlgorithm::Sha256),
+        _ => None,
+    }
+}
+
+fn read(file: &Path) -> Result<Vec<u8>> {
+    fs::read(file).map_err(|e| format!("{}: {}", file.display(), e))
+}
+
+fn open(file: &Path) -> Result<BufReader<File>> {
+    File::open(file)
+        .map(BufReader::new)
+        .map_err(|e| format!("{}: {}", file.display(), e))
+}
+
+// Imports an archive into a new memory store, verifying its entries
+fn import(file: &Path) -> Result<(Vec<Id>, StoreRef)> {
+    let reader = open(file)?;
+    let store = StoreRef::memory();
+    let roots = archive::import(&store, reader)
+        .map_err(|e| format!("{}: {}", file.display(), e))?;
+    Ok((roots, store))
+}
+
+// Checks every record of an archive, reporting those not matching their id
+fn verify(file: &Path) -> Result<bool> {
+    let reader = archive::Reader::new(open(file)?)
+        .map_err(|e| format!("{}: {}", file.display(), e))?;
+
+    let mut records = 0;
+    let mut mismatches = 0;
+    for record in reader {
+        let (id, bytes) =
+            record.map_err(|e| format!("{}: {}", file.display(), e))?;
+        records += 1;
+        if let Err(e) = id.verify(&bytes) {
+            eprintln!("{}: {:?}", id, e);
+            mismatches += 1;
+        }
+    }
+
+    match mismatches {
+        0 => println!("{} entries, ok", records),
+        _ => eprintln!("{} of {} entries do not match", mismatches, records),
+    }
+    Ok(mismatches == 0)
+}
+
+// The id of the contents of a file, as `Id::new` gives for the same bytes
+fn id_of(bytes: &[u8]) -> Result<Id> {
+    let len = u32::try_from(bytes.len())
+        .map_err(|_| String::from("file too large for an id"))?;
+    Ok(Id::raw(Store::hash(bytes), len))
+}
+
+fn dump(bytes: &[u8]) {
+    const MSB: u8 = 0b1000_0000;
+
+    println!("{:<8}{:<32}{:<24}zigzag", "offset", "bytes", "varint");
+
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let len =
+            bytes[offset..].iter().take_while(|b| *b & MSB != 0).count() + 1;
+
+        if offset + len > bytes.len() {
+            let rest = &bytes[offset..];
+            println!("{:<8x}{:<32}truncated", offset, spaced_hex(rest));
+            return;
+        }
+
+        let varint = &bytes[offset..offset + len];
+        match u64::decode(&mut Source::new(varint)) {
+            Ok(value) => {
+                let zigzag = (value >> 1) as i64 ^ -((value & 1) as i64);
+                println!(
+                    "{:<8x}{:<32}{:<24}{}",
+                    offset,
+                    spaced_hex(varint),
+                    value,
+                    zigzag
+                );
+            }
+            Err(_) => {
+                println!("{:<8x}{:<32}overflow", offset, spaced_hex(varint))
+            }
+        }
+        offset += len;
+    }
+}
+
+fn print_id(id: &Id) {
+    println!("debug: {:?}", id);
+    println!("canon: {}", to_hex(&id.encode_to_vec()));
+    println!("string: {}", id);
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn spaced_hex(bytes: &[u8]) -> String {
+    let hex: Vec<_> = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    hex.join(" ")
+}
+
+fn from_hex(hex: &str) -> Result<Vec<u8>> {
+    let hex = hex.trim_start_matches("0x");
+    let digits: Vec<u8> = hex
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| {
+            c.to_digit(16)
+                .map(|d| d as u8)
+                .ok_or_else(|| format!("invalid hex digit '{}'", c))
+        })
+        .collect::<Result<_>>()?;
+
+    if !digits.len().is_multiple_of(2) {
+        return Err("odd number of hex digits".into());
+    }
+    Ok(digits
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair[1])
+        .collect())
+}
+
+fn parse_hash(hex: &str) -> Option<IdHash> {
+    let bytes = from_hex(hex).ok()?;
+    let mut hash = IdHash::default();
+    if bytes.len() != hash.len() {
+        return None;
+    }
+    hash.copy_from_slice(&bytes);
+    Some(hash)
+}
+
+fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+
+    let config = hash_config(&mut args);
+    let command = match (config, Command::parse(&args)) {
+        (Some(config), Some(command)) => {
+            Store::set_hash_config(config);
+            command
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    match command.run() {
+        Ok(true) => (),
+        Ok(false) => process::exit(1),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+    }
+}
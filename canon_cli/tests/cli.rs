@@ -0,0 +1,264 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use canonical::archive;
+use canonical::{EncodeToVec, HashConfig, Id, Store, StoreRef};
+
+fn canon(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_canon"))
+        .args(args)
+        .output()
+        .expect("run canon")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// A temporary directory, removed when dropped
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn join(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn temp_dir(name: &str) -> TempDir {
+    let dir = std::env::temp_dir().join(format!(
+        "canon-cli-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    TempDir(dir)
+}
+
+#[test]
+fn hash_file() {
+    let dir = temp_dir("hash");
+    let file = dir.join("blob");
+    fs::write(&file, b"hello canon").unwrap();
+
+    let output = canon(&["hash", file.to_str().unwrap()]);
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output).trim(), to_hex(&Store::hash(b"hello canon")));
+}
+
+#[test]
+fn dump_varints() {
+    let output = canon(&["dump", "0x9601", "03ff"]);
+    let out = stdout(&output);
+    let lines: Vec<Vec<&str>> = out
+        .lines()
+        .map(|l| l.split_whitespace().collect())
+        .collect();
+
+    assert!(output.status.success());
+    assert_eq!(lines[1], ["0", "96", "01", "150", "75"]);
+    assert_eq!(lines[2], ["2", "03", "3", "-2"]);
+    assert_eq!(lines[3], ["3", "ff", "truncated"]);
+}
+
+// Writes an archive of the given values, returning their ids
+fn write_archive(path: &Path, values: &[Vec<u8>]) -> Vec<Id> {
+    let store = StoreRef::memory();
+    let ids: Vec<Id> = values.iter().map(|v| Id::new_in(v, &store)).collect();
+    let file = fs::File::create(path).unwrap();
+    archive::export(&store, &ids, |_, _| Ok(vec![]), file).unwrap();
+    ids
+}
+
+#[test]
+fn list_and_verify() {
+    let dir = temp_dir("archive");
+    let path = dir.join("archive");
+    let values = [vec![1u8; 100], vec![2u8; 50]];
+    let ids = write_archive(&path, &values);
+
+    let output = canon(&["list", path.to_str().unwrap()]);
+    let out = stdout(&output);
+
+    assert!(output.status.success());
+    for id in &ids {
+        assert!(out.contains(&format!("root {}", id)));
+        assert!(out.contains(&format!(
+            "{}  {}",
+            to_hex(&id.hash()),
+            id.size()
+        )));
+    }
+    let total: usize = ids.iter().map(Id::size).sum();
+    assert!(out.contains(&format!("2 entries, {} bytes", total)));
+
+    let output = canon(&["verify", path.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("2 entries, ok"));
+
+    // tamper with the last byte of both records, of 37 bytes of id each
+    let mut bytes = fs::read(&path).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 1;
+    bytes[last - 37 - ids[1].size()] ^= 1;
+    fs::write(&path, bytes).unwrap();
+
+    let output = canon(&["verify", path.to_str().unwrap()]);
+    let err = stderr(&output);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).is_empty());
+    for id in &ids {
+        assert!(err.contains(&format!("{}: Corrupted", id)));
+    }
+    assert!(err.contains("2 of 2 entries do not match"));
+
+    // reading stops at the end of the archive
+    let bytes = fs::read(&path).unwrap();
+    fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+    let output = canon(&["verify", path.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("io error"));
+}
+
+#[test]
+fn hash_configuration() {
+    let dir = temp_dir("config");
+    let file = dir.join("blob");
+    fs::write(&file, b"hello canon").unwrap();
+    let file = file.to_str().unwrap();
+
+    let domain = HashConfig::DEFAULT.with_domain(3, b"canon");
+    let output = canon(&["--domain", "3:canon", "hash", file]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output).trim(), to_hex(&domain.hash(b"hello canon")));
+
+    assert_eq!(
+        canon(&["--algorithm", "md5", "hash", file]).status.code(),
+        Some(2)
+    );
+    assert_eq!(
+        canon(&["--domain", "200:x", "hash", file]).status.code(),
+        Some(2)
+    );
+    assert_eq!(
+        canon(&["--domain", "3:a", "--domain", "4:b", "hash", file])
+            .status
+            .code(),
+        Some(2)
+    );
+}
+
+#[cfg(feature = "sha2")]
+#[test]
+fn sha256() {
+    let dir = temp_dir("sha256");
+    let file = dir.join("blob");
+    fs::write(&file, b"hello canon").unwrap();
+    let file = file.to_str().unwrap();
+
+    let sha256 = HashConfig::new(canonical::HashAlgorithm::Sha256);
+    let output = canon(&["hash", "--algorithm", "sha256", file]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output).trim(), to_hex(&sha256.hash(b"hello canon")));
+
+    let domain = sha256.with_domain(3, b"canon");
+    let output =
+        canon(&["--algorithm", "sha256", "--domain", "3:canon", "hash", file]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output).trim(), to_hex(&domain.hash(b"hello canon")));
+}
+
+#[test]
+fn id_of_file() {
+    let dir = temp_dir("id");
+    let small = dir.join("small");
+    let large = dir.join("large");
+    fs::write(&small, 7u32.encode_to_vec()).unwrap();
+    fs::write(&large, [9u8; 40]).unwrap();
+
+    // contents that would fit inline are hashed, as with `Id::new`
+    let output = canon(&["id", "--file", small.to_str().unwrap()]);
+    let id = Id::new_in(&7u32, &StoreRef::memory());
+    assert!(output.status.success());
+    assert!(stdout(&output).contains(&format!("string: {}", id)));
+    assert!(stdout(&output).contains(&format!(
+        "canon: 0001{}",
+        to_hex(&Store::hash(&7u32.encode_to_vec()))
+    )));
+
+    let output = canon(&["id", "--file", large.to_str().unwrap()]);
+    assert!(output.status.success());
+    let out = stdout(&output);
+    assert!(
+        out.contains(&format!("canon: 0028{}", to_hex(&Store::hash(&[9; 40]))))
+    );
+
+    #[cfg(feature = "blake3")]
+    {
+        let output = canon(&[
+            "id",
+            "--file",
+            large.to_str().unwrap(),
+            "--algorithm",
+            "blake3",
+        ]);
+        let blake3 = HashConfig::new(canonical::HashAlgorithm::Blake3);
+        assert!(output.status.success());
+        let out = stdout(&output);
+        assert!(out.contains(&format!(
+            "canon: 0228{}",
+            to_hex(&blake3.hash(&[9; 40]))
+        )));
+    }
+}
+
+#[test]
+fn print_id() {
+    let id = Id::raw([0xab; 32], 300);
+    let output = canon(&["id", &to_hex(&[0xab; 32]), "300"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        format!(
+            "debug: {:?}\ncanon: {}\nstring: {}\n",
+            id,
+            to_hex(&id.encode_to_vec()),
+            id
+        )
+    );
+
+    let parsed = canon(&["id", &id.to_string()]);
+    assert!(parsed.status.success());
+    assert_eq!(stdout(&parsed), stdout(&output));
+}
+
+#[test]
+fn usage() {
+    let output = canon(&["frobnicate"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Usage"));
+}
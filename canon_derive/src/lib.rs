@@ -247,3 +247,209 @@ pub fn canon_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 
     proc_macro::TokenStream::from(output)
 }
+
+fn add_schema_bounds(mut generics: Generics) -> Generics {
+    for param in &mut generics.params {
+        if let GenericParam::Type(ref mut type_param) = *param {
+            type_param.bounds.push(parse_quote!(canonical::CanonSchema));
+        }
+    }
+    generics
+}
+
+fn fields_schema(fields: &Fields) -> proc_macro2::TokenStream {
+    match fields {
+        Fields::Named(ref fields) if fields.named.is_empty() => {
+            quote! { canonical::Fields::Named(::core::default::Default::default()) }
+        }
+        Fields::Unnamed(ref fields) if fields.unnamed.is_empty() => {
+            quote! { canonical::Fields::Unnamed(::core::default::Default::default()) }
+        }
+        Fields::Named(ref fields) => {
+            let fields = fields.named.iter().map(|f| {
+                let name = f.ident.as_ref().map(|ident| ident.to_string());
+                let ty = &f.ty;
+                quote_spanned! { f.span() =>
+                    (
+                        ::core::convert::From::from(#name),
+                        <#ty as canonical::CanonSchema>::schema(),
+                    )
+                }
+            });
+            quote! {
+                canonical::Fields::Named(
+                    ::core::convert::From::from([ #( #fields ),* ])
+                )
+            }
+        }
+        Fields::Unnamed(ref fields) => {
+            let fields = fields.unnamed.iter().map(|f| {
+                let ty = &f.ty;
+                quote_spanned! { f.span() =>
+                    <#ty as canonical::CanonSchema>::schema()
+                }
+            });
+            quote! {
+                canonical::Fields::Unnamed(
+                    ::core::convert::From::from([ #( #fields ),* ])
+                )
+            }
+        }
+        Fields::Unit => quote! { canonical::Fields::Unit },
+    }
+}
+
+#[proc_macro_derive(CanonSchema)]
+/// Derive macro that describes the encoding derived by `Canon`
+pub fn canon_schema_derive(
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = input.ident.clone();
+    let name_str = name.to_string();
+
+    let generics = add_schema_bounds(input.generics.clone());
+
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+
+    let schema = match input.data {
+        Data::Struct(ref data) => {
+            let fields = fields_schema(&data.fields);
+            quote! {
+                canonical::Schema::Struct {
+                    name: ::core::convert::From::from(#name_str),
+                    fields: #fields,
+                }
+            }
+        }
+        Data::Enum(ref data) => {
+            if data.variants.len() > 256 {
+                unimplemented!(
+                    "More than 256 enum variants is not supported at the time."
+                )
+            }
+
+            let variants = data.variants.iter().enumerate().map(|(i, v)| {
+                let tag = Literal::u8_suffixed(i as u8);
+                let variant = v.ident.to_string();
+                let fields = fields_schema(&v.fields);
+                quote! {
+                    canonical::Variant {
+                        name: ::core::convert::From::from(#variant),
+                        tag: #tag,
+                        fields: #fields,
+                    }
+                }
+            });
+
+            let variants = if data.variants.is_empty() {
+                quote! { ::core::default::Default::default() }
+            } else {
+                quote! { ::core::convert::From::from([ #( #variants ),* ]) }
+            };
+
+            quote! {
+                canonical::Schema::Enum {
+                    name: ::core::convert::From::from(#name_str),
+                    variants: #variants,
+                }
+            }
+        }
+        Data::Union(_) => unimplemented!("Union types are not derivable"),
+    };
+
+    let output = quote! {
+        impl #generics canonical::CanonSchema for #name #ty_generics #where_clause {
+            fn schema() -> canonical::Schema {
+                #schema
+            }
+        }
+    };
+
+    proc_macro::TokenStream::from(output)
+}
+
+fn add_visit_bounds(mut generics: Generics) -> Generics {
+    for param in &mut generics.params {
+        if let GenericParam::Type(ref mut type_param) = *param {
+            type_param.bounds.push(parse_quote!(canonical::VisitIds));
+        }
+    }
+    generics
+}
+
+#[proc_macro_derive(VisitIds)]
+/// Derive macro that visits the `Id`s in each field, in encoding order
+pub fn visit_ids_derive(
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = input.ident.clone();
+
+    let generics = add_visit_bounds(input.generics.clone());
+
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+
+    let visit = match input.data {
+        Data::Struct(ref data) => {
+            let fields = data.fields.iter().enumerate().map(|(i, f)| {
+                let field = match f.ident {
+                    Some(ref ident) => quote! { #ident },
+                    None => {
+                        let i = Literal::usize_unsuffixed(i);
+                        quote! { #i }
+                    }
+                };
+                quote_spanned! { f.span() =>
+                    canonical::VisitIds::visit_ids(&self.#field, visit);
+                }
+            });
+            quote! { #( #fields )* }
+        }
+        Data::Enum(ref data) => {
+            let arms = data.variants.iter().map(|v| {
+                let ident = &v.ident;
+                let binds: Vec<_> = v
+                    .fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, f)| match f.ident {
+                        Some(ref ident) => ident.clone(),
+                        None => Ident::new(FIELD_NAMES[i], f.span()),
+                    })
+                    .collect();
+                let pattern = match v.fields {
+                    Fields::Named(_) => quote! { { #( #binds ),* } },
+                    Fields::Unnamed(_) => quote! { ( #( #binds ),* ) },
+                    Fields::Unit => quote! {},
+                };
+                quote! {
+                    #name :: #ident #pattern => {
+                        #( canonical::VisitIds::visit_ids(#binds, visit); )*
+                    }
+                }
+            });
+            if data.variants.is_empty() {
+                quote! { match *self {} }
+            } else {
+                quote! {
+                    match self {
+                        #( #arms )*
+                    }
+                }
+            }
+        }
+        Data::Union(_) => unimplemented!("Union types are not derivable"),
+    };
+
+    let output = quote! {
+        impl #generics canonical::VisitIds for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn visit_ids(&self, visit: &mut dyn FnMut(&canonical::Id)) {
+                #visit
+            }
+        }
+    };
+
+    proc_macro::TokenStream::from(output)
+}
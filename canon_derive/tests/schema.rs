@@ -0,0 +1,167 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use canonical::{Canon, CanonSchema, Fields, Id, Schema, Variant};
+use canonical_derive::{Canon, CanonSchema};
+
+#[derive(Clone, Canon, CanonSchema)]
+struct A {
+    a: u64,
+    b: u8,
+}
+
+#[derive(Clone, Canon, CanonSchema)]
+struct B(u64, Vec<u8>);
+
+#[derive(Clone, Canon, CanonSchema)]
+struct C;
+
+#[derive(Clone, Canon, CanonSchema)]
+struct Empty {}
+
+#[derive(Clone, Canon, CanonSchema)]
+enum E {
+    A,
+    B(Option<u32>),
+    C { alice: u64, bob: Id },
+}
+
+#[derive(CanonSchema)]
+enum Never {}
+
+#[derive(Clone, Canon, CanonSchema)]
+struct H<T>(T);
+
+#[test]
+fn named_struct() {
+    assert_eq!(
+        A::schema(),
+        Schema::Struct {
+            name: "A".into(),
+            fields: Fields::Named(vec![
+                (
+                    "a".into(),
+                    Schema::Varint {
+                        bits: 64,
+                        signed: false
+                    }
+                ),
+                ("b".into(), Schema::U8),
+            ]),
+        }
+    );
+}
+
+#[test]
+fn unnamed_and_unit_structs() {
+    assert_eq!(
+        B::schema(),
+        Schema::Struct {
+            name: "B".into(),
+            fields: Fields::Unnamed(vec![
+                Schema::Varint {
+                    bits: 64,
+                    signed: false
+                },
+                Schema::Sequence(Box::new(Schema::U8)),
+            ]),
+        }
+    );
+
+    assert_eq!(
+        C::schema(),
+        Schema::Struct {
+            name: "C".into(),
+            fields: Fields::Unit,
+        }
+    );
+
+    assert_eq!(
+        Empty::schema(),
+        Schema::Struct {
+            name: "Empty".into(),
+            fields: Fields::Named(vec![]),
+        }
+    );
+}
+
+#[test]
+fn enums() {
+    assert_eq!(
+        E::schema(),
+        Schema::Enum {
+            name: "E".into(),
+            variants: vec![
+                Variant {
+                    name: "A".into(),
+                    tag: 0,
+                    fields: Fields::Unit,
+                },
+                Variant {
+                    name: "B".into(),
+                    tag: 1,
+                    fields: Fields::Unnamed(vec![Schema::Option(Box::new(
+                        Schema::Varint {
+                            bits: 32,
+                            signed: false
+                        }
+                    ))]),
+                },
+                Variant {
+                    name: "C".into(),
+                    tag: 2,
+                    fields: Fields::Named(vec![
+                        (
+                            "alice".into(),
+                            Schema::Varint {
+                                bits: 64,
+                                signed: false
+                            }
+                        ),
+                        ("bob".into(), Schema::Id),
+                    ]),
+                },
+            ],
+        }
+    );
+
+    assert_eq!(
+        Never::schema(),
+        Schema::Enum {
+            name: "Never".into(),
+            variants: vec![],
+        }
+    );
+}
+
+#[test]
+fn generics() {
+    assert_eq!(
+        H::<bool>::schema(),
+        Schema::Struct {
+            name: "H".into(),
+            fields: Fields::Unnamed(vec![Schema::Bool]),
+        }
+    );
+}
+
+#[test]
+fn json() {
+    assert_eq!(
+        E::schema().to_json(),
+        concat!(
+            r#"{"type":"enum","name":"E","variants":["#,
+            r#"{"name":"A","tag":0,"kind":"unit","fields":[]},"#,
+            r#"{"name":"B","tag":1,"kind":"unnamed","fields":["#,
+            r#"{"schema":{"type":"option","some":"#,
+            r#"{"type":"varint","bits":32,"signed":false}}}]},"#,
+            r#"{"name":"C","tag":2,"kind":"named","fields":["#,
+            r#"{"name":"alice","schema":"#,
+            r#"{"type":"varint","bits":64,"signed":false}},"#,
+            r#"{"name":"bob","schema":{"type":"id"}}]}]}"#,
+        )
+    );
+}
@@ -0,0 +1,144 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use arbitrary::{Arbitrary, Unstructured};
+use canonical::{
+    Canon, CanonSchema, EncodeToVec, FieldValues, Id, Schema, Value,
+};
+use canonical_derive::{Canon, CanonSchema};
+
+#[derive(Clone, Canon, CanonSchema, Debug, Arbitrary)]
+struct A {
+    a: u64,
+    b: i32,
+}
+
+#[derive(Clone, Canon, CanonSchema, Debug, Arbitrary)]
+struct B(Vec<u8>, String);
+
+#[derive(Clone, Canon, CanonSchema, Debug, Arbitrary)]
+enum E {
+    A,
+    B(Option<u32>),
+    C { alice: Vec<A>, bob: (bool, B) },
+}
+
+#[derive(Clone, Canon, CanonSchema, Debug)]
+struct Node {
+    value: E,
+    child: Option<Id>,
+}
+
+fn schema_value<T: Canon + CanonSchema>(t: &T) -> Value {
+    Value::from_bytes(&T::schema(), &t.encode_to_vec()).unwrap()
+}
+
+#[test]
+fn decode_struct() {
+    let a = A { a: 7, b: -3 };
+
+    assert_eq!(
+        schema_value(&a),
+        Value::Struct {
+            name: "A".into(),
+            fields: FieldValues::Named(vec![
+                ("a".into(), Value::UInt(7)),
+                ("b".into(), Value::Int(-3)),
+            ]),
+        }
+    );
+}
+
+#[test]
+fn decode_arbitrary() {
+    let mut u = Unstructured::new(&[0x5a; 4096]);
+    for _ in 0..32 {
+        let e = E::arbitrary(&mut u).unwrap();
+        let bytes = e.encode_to_vec();
+        assert!(Value::from_bytes(&E::schema(), &bytes).is_ok());
+    }
+}
+
+#[test]
+fn mismatched_schema() {
+    let bytes = A { a: 300, b: 1 }.encode_to_vec();
+
+    assert!(Value::from_bytes(&B::schema(), &bytes).is_err());
+    assert!(Value::from_bytes(&Schema::U8, &bytes).is_err());
+    assert!(Value::from_bytes(&Schema::Id, &bytes).is_err());
+}
+
+#[test]
+fn reify_nested_ids() {
+    let leaf = Node {
+        value: E::B(Some(5)),
+        child: None,
+    };
+    let root = Node {
+        value: E::A,
+        child: Some(Id::new(&leaf)),
+    };
+    let root_id = Id::new(&root);
+
+    let value = Value::reify(&root_id, &Node::schema()).unwrap();
+    let child = match value {
+        Value::Struct {
+            fields: FieldValues::Named(fields),
+            ..
+        } => match &fields[1].1 {
+            Value::Option(Some(id)) => match **id {
+                Value::Id(id) => id,
+                _ => panic!("expected an id"),
+            },
+            _ => panic!("expected a child"),
+        },
+        _ => panic!("expected a struct"),
+    };
+
+    assert_eq!(
+        Value::reify(&child, &Node::schema()).unwrap(),
+        schema_value(&leaf)
+    );
+}
+
+#[test]
+fn display() {
+    let e = E::C {
+        alice: vec![A { a: 1, b: -1 }],
+        bob: (true, B(vec![0xde, 0xad], "hi".into())),
+    };
+    let value = schema_value(&e);
+
+    assert_eq!(
+        format!("{}", value),
+        r#"E::C { alice: [A { a: 1, b: -1 }], bob: (true, B(0xdead, "hi")) }"#
+    );
+
+    assert_eq!(
+        format!("{:#}", value),
+        r#"E::C {
+    alice: [
+        A {
+            a: 1,
+            b: -1,
+        },
+    ],
+    bob: (
+        true,
+        B(
+            0xdead,
+            "hi",
+        ),
+    ),
+}"#
+    );
+
+    assert_eq!(format!("{}", schema_value(&E::A)), "E::A");
+    assert_eq!(
+        format!("{:#}", schema_value(&E::B(None))),
+        "E::B(\n    None,\n)"
+    );
+}
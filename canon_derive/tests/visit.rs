@@ -0,0 +1,102 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use canonical::{Canon, Id, Order, TypedId, VisitIds, Walk};
+use canonical_derive::{Canon, VisitIds};
+
+#[derive(Clone, Canon, VisitIds)]
+struct A {
+    a: Id,
+    b: u64,
+    c: Option<Id>,
+}
+
+#[derive(Clone, Canon, VisitIds)]
+struct B(Vec<Id>, u8);
+
+#[derive(Clone, Canon, VisitIds)]
+struct C;
+
+#[derive(Clone, Canon, VisitIds)]
+enum E {
+    A,
+    B(Id, Id),
+    C { alice: u64, bob: Id },
+}
+
+#[derive(VisitIds)]
+enum Never {}
+
+#[derive(Clone, Canon, VisitIds)]
+struct H<T>(T);
+
+#[derive(Clone, Canon, VisitIds)]
+enum Tree {
+    Leaf(u32),
+    Node(Vec<TypedId<Tree>>),
+}
+
+fn ids(n: u8) -> Vec<Id> {
+    (0..n).map(|i| Id::new(&[i; 40])).collect()
+}
+
+#[test]
+fn structs() {
+    let i = ids(3);
+
+    let a = A {
+        a: i[0],
+        b: 1,
+        c: Some(i[1]),
+    };
+    assert_eq!(a.ids(), vec![i[0], i[1]]);
+
+    assert_eq!(B(i.clone(), 0).ids(), i);
+    assert_eq!(C.ids(), vec![]);
+    assert_eq!(H(A { c: None, ..a }).ids(), vec![i[0]]);
+}
+
+#[test]
+fn enums() {
+    let i = ids(2);
+
+    assert_eq!(E::A.ids(), vec![]);
+    assert_eq!(E::B(i[1], i[0]).ids(), vec![i[1], i[0]]);
+    assert_eq!(
+        E::C {
+            alice: 1,
+            bob: i[0]
+        }
+        .ids(),
+        vec![i[0]]
+    );
+
+    let _ = <Never as VisitIds>::visit_ids;
+}
+
+#[test]
+fn walk_tree() {
+    let leaf = |n| TypedId::new(&Tree::Leaf(n));
+    let left = TypedId::new(&Tree::Node(vec![leaf(1), leaf(2)]));
+    let root = Tree::Node(vec![left, leaf(3)]);
+    let root = Id::new(&root);
+
+    let leaves = |order| -> Vec<u32> {
+        Walk::of::<Tree>(&[root], order)
+            .map(|item| {
+                let (id, _) = item.unwrap();
+                id.reify().unwrap()
+            })
+            .filter_map(|tree| match tree {
+                Tree::Leaf(n) => Some(n),
+                Tree::Node(_) => None,
+            })
+            .collect()
+    };
+
+    assert_eq!(leaves(Order::DepthFirst), vec![1, 2, 3]);
+    assert_eq!(leaves(Order::BreadthFirst), vec![3, 1, 2]);
+}